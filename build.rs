@@ -31,7 +31,11 @@ fn main() {
     // `memory.x` is changed.
     println!("cargo:rerun-if-changed=memory.x");
 
-    // Specify linker arguments.
+    // Specify linker arguments. Host builds, such as `cargo test --lib` on the
+    // development machine, link normally.
+    if !env::var("TARGET").unwrap().starts_with("thumb") {
+        return;
+    }
 
     // `--nmagic` is required if memory section addresses are not aligned to 0x10000,
     // for example the FLASH and RAM sections in your `memory.x`.
@@ -0,0 +1,178 @@
+#![no_std]
+#![no_main]
+
+// 1本のSPIバスに2個のICM42688(メインと冗長系)を繋ぎ、CSピンだけ分けて両方から読む例。
+
+use panic_halt as _;
+
+use core::cell::RefCell;
+use core::convert::Infallible;
+
+use cortex_m::asm;
+
+use stm32f1xx_hal:: {
+	pac,
+	rcc,
+	flash,
+	afio,
+	spi,
+	prelude::*,
+};
+
+use embedded_halv02::blocking::spi as spiv02;
+use embedded_halv02::digital::v2::OutputPin as OutputPinV02;
+
+use embedded_hal::digital::{self, OutputPin};
+use embedded_hal::spi::{self as spiv1, SpiBus};
+use embedded_hal_bus::spi::RefCellDevice;
+
+use icm42688_portable::Icm42688;
+use icm42688_portable::register::bank0::{AccelMode, GyroMode};
+
+// stm32f1xx-halはembedded-hal 0.2しか実装していないので、1.0のSpiBusに変換する。
+struct SpiV02<S>(S);
+
+#[derive(Debug)]
+struct SpiV02Error;
+
+impl spiv1::Error for SpiV02Error {
+	fn kind(&self) -> spiv1::ErrorKind {
+		spiv1::ErrorKind::Other
+	}
+}
+
+impl<S> spiv1::ErrorType for SpiV02<S> {
+	type Error = SpiV02Error;
+}
+
+impl<S: spiv02::Transfer<u8> + spiv02::Write<u8>> SpiBus for SpiV02<S> {
+	fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+		words.fill(0);
+		self.0.transfer(words).map(|_| ()).map_err(|_| SpiV02Error)
+	}
+
+	fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+		spiv02::Write::write(&mut self.0, words).map_err(|_| SpiV02Error)
+	}
+
+	fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+		// 長さが違う場合、足りない分は0を送る/余った分は捨てる
+		for i in 0..read.len().max(write.len()) {
+			let mut word = [write.get(i).copied().unwrap_or(0)];
+			self.0.transfer(&mut word).map_err(|_| SpiV02Error)?;
+			if let Some(r) = read.get_mut(i) {
+				*r = word[0];
+			}
+		}
+		Ok(())
+	}
+
+	fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+		self.0.transfer(words).map(|_| ()).map_err(|_| SpiV02Error)
+	}
+
+	fn flush(&mut self) -> Result<(), Self::Error> {
+		Ok(())
+	}
+}
+
+// CSピンも同様に変換する。
+struct PinV02<P>(P);
+
+impl<P> digital::ErrorType for PinV02<P> {
+	type Error = Infallible;
+}
+
+impl<P: OutputPinV02<Error = Infallible>> OutputPin for PinV02<P> {
+	fn set_low(&mut self) -> Result<(), Self::Error> {
+		self.0.set_low()
+	}
+
+	fn set_high(&mut self) -> Result<(), Self::Error> {
+		self.0.set_high()
+	}
+}
+
+// ドライバはasyncのDelayNsを要求するので、ビジーループで実装する。
+struct BusyDelay {
+	sysclk_hz: u32,
+}
+
+impl embedded_hal_async::delay::DelayNs for BusyDelay {
+	async fn delay_ns(&mut self, ns: u32) {
+		asm::delay((self.sysclk_hz as u64 * ns as u64 / 1_000_000_000) as u32 + 1);
+	}
+}
+
+#[cortex_m_rt::entry]
+fn main() -> ! {
+	let dp = pac::Peripherals::take().unwrap();
+
+	let rcc_p: rcc::Rcc = dp.RCC.constrain();
+	let mut flash_p: flash::Parts = dp.FLASH.constrain();
+
+	// AFIO_MAPRを変更するのでAFIOのクロックを供給しておく。
+	unsafe {
+		let rcc_p = pac::Peripherals::steal().RCC;
+		rcc_p.apb2enr.write(|w| { w.afioen().set_bit() });
+	}
+
+	let mut gpioa = dp.GPIOA.split();
+	let mut gpiob = dp.GPIOB.split();
+
+	let mut afio_p: afio::Parts = dp.AFIO.constrain();
+	let (_pa15, pb3, pb4) = afio_p.mapr.disable_jtag(gpioa.pa15, gpiob.pb3, gpiob.pb4);
+
+	let clocks = rcc_p.cfgr.use_hse(8.MHz()).sysclk(72.MHz()).pclk1(36.MHz()).freeze(&mut flash_p.acr);
+
+	let pins = (
+		pb3.into_alternate_push_pull(&mut gpiob.crl), // SCK
+		pb4.into_floating_input(&mut gpiob.crl), // MISO
+		gpiob.pb5.into_alternate_push_pull(&mut gpiob.crl), // MOSI
+	);
+
+	// ICM42688参照
+	let spi_mode = spi::Mode {
+		polarity: spi::Polarity::IdleHigh,
+		phase: spi::Phase::CaptureOnSecondTransition,
+	};
+
+	let spi_p = spi::Spi::spi1(dp.SPI1, pins, &mut afio_p.mapr, spi_mode, 24.MHz(), clocks);
+
+	// CSはそれぞれ別のピン。初期状態はHigh(非選択)。
+	let mut cs_primary = gpioa.pa4.into_push_pull_output(&mut gpioa.crl);
+	let mut cs_redundant = gpioa.pa3.into_push_pull_output(&mut gpioa.crl);
+	cs_primary.set_high();
+	cs_redundant.set_high();
+
+	// RefCellDeviceでバスを共有する。割り込みからも触るならCriticalSectionDeviceにする。
+	let bus = RefCell::new(SpiV02(spi_p));
+	let primary = RefCellDevice::new_no_delay(&bus, PinV02(cs_primary)).unwrap();
+	let redundant = RefCellDevice::new_no_delay(&bus, PinV02(cs_redundant)).unwrap();
+
+	// SPIだけで使うので、I2C/I3Cは無効化しておく。
+	let mut imu_primary = Icm42688::new_blocking(primary).lock_to_spi();
+	let mut imu_redundant = Icm42688::new_blocking(redundant).lock_to_spi();
+
+	let mut delay = BusyDelay { sysclk_hz: clocks.sysclk().raw() };
+
+	// ブロッキングなデバイスなので、futureは最初のpollで完了する。
+	embassy_futures::block_on(async {
+		imu_primary.init(&mut delay).await.unwrap();
+		imu_redundant.init(&mut delay).await.unwrap();
+		imu_primary.set_power(GyroMode::LowNoise, AccelMode::LowNoise, &mut delay).await.unwrap();
+		imu_redundant.set_power(GyroMode::LowNoise, AccelMode::LowNoise, &mut delay).await.unwrap();
+	});
+
+	loop {
+		let (a, b) = embassy_futures::block_on(async {
+			(imu_primary.read_all().await.unwrap(), imu_redundant.read_all().await.unwrap())
+		});
+
+		// 2個の差が大きければどちらかが壊れている
+		let diff = a.gyro.dps().iter().zip(b.gyro.dps()).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+		if diff > 10.0 {
+			asm::bkpt();
+		}
+	}
+}
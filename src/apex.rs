@@ -0,0 +1,216 @@
+use crate::interrupt::InterruptPin;
+use crate::register::bank0::{ActivityClass, ApexData4, ApexData5, DmpOdr, Odr, TapAxis, TapDir, TapNum};
+use crate::register::bank4::{SensitivityMode, SleepTimeOut, TiltWaitTime};
+
+// State of the DMP and its APEX features. Every change goes through the same DMP initialisation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApexSettings {
+	pub dmp_odr: DmpOdr,
+	pub dmp_power_save: bool,
+	pub dmp_power_save_time_sel: u8,
+	pub sensitivity_mode: SensitivityMode,  // APEX_CONFIG9
+	pub pedometer: Option<PedometerSettings>,
+	pub tilt: Option<TiltSettings>,
+	pub r2w: Option<R2wSettings>,
+	pub tap: Option<TapSettings>,
+}
+
+impl ApexSettings {
+	// power-on reset values, no feature enabled
+	pub const RESET: ApexSettings = ApexSettings {
+		dmp_odr: DmpOdr::Hz50,
+		dmp_power_save: true,
+		dmp_power_save_time_sel: 2,
+		sensitivity_mode: SensitivityMode::LowPower,
+		pedometer: None,
+		tilt: None,
+		r2w: None,
+		tap: None,
+	};
+
+	pub fn any_enabled(&self) -> bool {
+		self.pedometer.is_some() || self.tilt.is_some() || self.r2w.is_some() || self.tap.is_some()
+	}
+}
+
+// Selector indices into the pedometer threshold tables of the datasheet (APEX_CONFIG1..3).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PedometerSettings {
+	pub low_energy_amp_th_sel: u8,
+	pub amp_th_sel: u8,
+	pub step_cnt_th_sel: u8,  // steps before the count starts increasing
+	pub step_det_th_sel: u8,  // steps before step detection interrupts start
+	pub sb_timer_th_sel: u8,  // step buffer timeout
+	pub hi_en_th_sel: u8,  // high energy threshold, filters out shaking
+	pub pin: Option<InterruptPin>,  // step detection interrupt
+}
+
+impl Default for PedometerSettings {
+	// power-on reset values
+	fn default() -> Self {
+		Self {
+			low_energy_amp_th_sel: 10,
+			amp_th_sel: 8,
+			step_cnt_th_sel: 5,
+			step_det_th_sel: 2,
+			sb_timer_th_sel: 4,
+			hi_en_th_sel: 1,
+			pin: None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PedometerData {
+	pub step_count: u16,
+	pub cadence_raw: u8,  // samples per step at the DMP ODR, u6.2
+	pub activity: ActivityClass,
+	pub dmp_idle: bool,
+}
+
+impl PedometerData {
+	pub fn cadence_hz(&self, dmp_odr: DmpOdr) -> f32 {
+		match self.cadence_raw {
+			0 => 0.0,
+			raw => dmp_odr.hz() * 4.0 / raw as f32,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TiltSettings {
+	pub wait_time: TiltWaitTime,  // how long the tilt has to be held
+	pub pin: Option<InterruptPin>,
+}
+
+impl Default for TiltSettings {
+	fn default() -> Self {
+		Self { wait_time: TiltWaitTime::S4, pin: None }
+	}
+}
+
+// The device was tilted by more than 35 degrees and held for the configured wait time.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TiltEvent;
+
+// Raise-to-wake / raise-to-sleep gesture. The DMP uses the driver's `Mounting` to know which way is up.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct R2wSettings {
+	pub sleep_time_out: SleepTimeOut,  // no motion for this long reports sleep
+	pub sleep_gesture_delay: u8,  // (n + 1) * 0.32 s
+	pub pin: Option<InterruptPin>,  // wake and sleep interrupts
+}
+
+impl Default for R2wSettings {
+	// power-on reset values
+	fn default() -> Self {
+		Self {
+			sleep_time_out: SleepTimeOut::S6_40,
+			sleep_gesture_delay: 4,
+			pin: None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum R2wEvent {
+	Wake,
+	Sleep,
+}
+
+// Tap detection thresholds and timing (APEX_CONFIG7..8). Tap runs on the accel data directly,
+// which has to be in low noise mode at 1 kHz with 3rd order filters.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TapSettings {
+	pub min_jerk_thr: u8,  // 6 bit
+	pub max_peak_tol: u8,  // 2 bit
+	pub tmax: u8,  // 2 bit, longest time a double tap may take
+	pub tmin: u8,  // 2 bit, shortest time between two taps
+	pub smudge_reject_thr: u8,  // 3 bit
+	pub pin: Option<InterruptPin>,
+}
+
+impl TapSettings {
+	pub const ACCEL_ODR: Odr = Odr::Hz1k;
+}
+
+impl Default for TapSettings {
+	// power-on reset values
+	fn default() -> Self {
+		Self {
+			min_jerk_thr: 17,
+			max_peak_tol: 1,
+			tmax: 2,
+			tmin: 3,
+			smudge_reject_thr: 3,
+			pin: None,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TapCount {
+	Single,
+	Double,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TapEvent {
+	pub count: TapCount,
+	pub axis: TapAxis,
+	pub direction: TapDir,
+	pub double_tap_timing: u8,  // 16 accel samples per LSB, 0 for single taps
+}
+
+impl TapEvent {
+	pub fn new(apex_data4: ApexData4, apex_data5: ApexData5) -> Option<TapEvent> {
+		let (num, axis, direction) = apex_data4.depack();
+		let count = match num {
+			TapNum::Single => TapCount::Single,
+			TapNum::Double => TapCount::Double,
+			TapNum::None | TapNum::Reserved => return None,
+		};
+		let double_tap_timing = if count == TapCount::Double { apex_data5.depack() } else { 0 };
+		Some(TapEvent { count, axis, direction, double_tap_timing })
+	}
+
+	pub fn double_tap_interval_us(&self, accel_odr: Odr) -> f32 {
+		self.double_tap_timing as f32 * 16.0 * accel_odr.period_us()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn cadence_is_u6_2_samples_per_step() {
+		let data = PedometerData { step_count: 0, cadence_raw: 41, activity: ActivityClass::Walk, dmp_idle: false };
+		// 41 / 4 = 10.25 samples per step
+		assert_eq!(data.cadence_hz(DmpOdr::Hz25), 25.0 / 10.25);
+		assert_eq!(PedometerData { cadence_raw: 0, ..data }.cadence_hz(DmpOdr::Hz50), 0.0);
+	}
+
+	#[test]
+	fn tap_event_decoding() {
+		// double tap on Z in the negative direction, 5 * 16 samples apart, reserved bits of APEX_DATA5 set
+		let double = TapEvent::new(ApexData4(0b1_0101), ApexData5(0b1100_0101)).unwrap();
+		assert_eq!(double, TapEvent { count: TapCount::Double, axis: TapAxis::Z, direction: TapDir::Negative, double_tap_timing: 5 });
+		assert_eq!(double.double_tap_interval_us(Odr::Hz1k), 80_000.0);
+
+		let single = TapEvent::new(ApexData4(0b0_1010), ApexData5(5)).unwrap();
+		assert_eq!(single, TapEvent { count: TapCount::Single, axis: TapAxis::Y, direction: TapDir::Positive, double_tap_timing: 0 });
+		assert_eq!(TapEvent::new(ApexData4(0), ApexData5(0)), None);
+		assert_eq!(TapEvent::new(ApexData4(0b1_1000), ApexData5(0)), None);
+	}
+}
@@ -0,0 +1,197 @@
+use crate::register::bank0::{AccelFs, AccelMode, GyroFs, GyroMode, Odr};
+
+// One configuration shared by every sensor of an array.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ArrayConfig {
+	pub accel_fs: AccelFs,
+	pub accel_odr: Odr,
+	pub gyro_fs: GyroFs,
+	pub gyro_odr: Odr,
+	pub accel_mode: AccelMode,
+	pub gyro_mode: GyroMode,
+}
+
+impl Default for ArrayConfig {
+	fn default() -> Self {
+		Self {
+			accel_fs: AccelFs::G16,
+			accel_odr: Odr::Hz1k,
+			gyro_fs: GyroFs::Dps2000,
+			gyro_odr: Odr::Hz1k,
+			accel_mode: AccelMode::LowNoise,
+			gyro_mode: GyroMode::LowNoise,
+		}
+	}
+}
+
+// A sample rotated into the body frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BodySample {
+	pub accel_g: [f32; 3],
+	pub gyro_dps: [f32; 3],
+	pub temperature_c: f32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SensorHealth {
+	Ok,
+	ReadFailed,
+	Disagrees,  // further than the limits from the consensus
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Fusion {
+	Average,  // mean of the agreeing sensors
+	Median,  // per axis median of the agreeing sensors, needs three sensors to outvote one
+}
+
+// Largest distance from the consensus on any axis before a sensor is reported as disagreeing.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DisagreementLimits {
+	pub accel_g: f32,
+	pub gyro_dps: f32,
+}
+
+impl Default for DisagreementLimits {
+	fn default() -> Self {
+		Self { accel_g: 0.1, gyro_dps: 5.0 }
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ArraySample<const N: usize> {
+	pub sensors: [Option<BodySample>; N],
+	pub health: [SensorHealth; N],
+	pub fused: Option<BodySample>,  // `None` when no sensor agrees with the consensus
+}
+
+impl<const N: usize> ArraySample<N> {
+	// The consensus is the per axis median of every sensor that could be read. Sensors too far from it are
+	// flagged, and the rest are fused again without them. With two sensors a disagreement can't be
+	// attributed, so both are flagged and there is no fused sample.
+	pub fn fuse(sensors: [Option<BodySample>; N], fusion: Fusion, limits: DisagreementLimits) -> Self {
+		let mut health = sensors.map(|s| if s.is_some() { SensorHealth::Ok } else { SensorHealth::ReadFailed });
+		let Some(consensus) = combine(&sensors, &health, Fusion::Median) else {
+			return Self { sensors, health, fused: None };
+		};
+		for (sample, health) in sensors.iter().zip(health.iter_mut()) {
+			let Some(sample) = sample else {
+				continue;
+			};
+			let accel_off = max_distance(sample.accel_g, consensus.accel_g);
+			let gyro_off = max_distance(sample.gyro_dps, consensus.gyro_dps);
+			if accel_off > limits.accel_g || gyro_off > limits.gyro_dps {
+				*health = SensorHealth::Disagrees;
+			}
+		}
+		let fused = combine(&sensors, &health, fusion);
+		Self { sensors, health, fused }
+	}
+
+	pub fn all_ok(&self) -> bool {
+		self.health.iter().all(|h| *h == SensorHealth::Ok)
+	}
+}
+
+fn max_distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+	(0..3).map(|i| (a[i] - b[i]).abs()).fold(0.0, f32::max)
+}
+
+fn combine<const N: usize>(sensors: &[Option<BodySample>; N], health: &[SensorHealth; N], fusion: Fusion) -> Option<BodySample> {
+	let mut used = [BodySample { accel_g: [0.0; 3], gyro_dps: [0.0; 3], temperature_c: 0.0 }; N];
+	let mut count = 0;
+	for (sample, health) in sensors.iter().zip(health) {
+		if let (Some(sample), SensorHealth::Ok) = (sample, health) {
+			used[count] = *sample;
+			count += 1;
+		}
+	}
+	let used = &used[..count];
+	if used.is_empty() {
+		return None;
+	}
+	// disabled axes read NaN and are left out, an axis no sensor measures stays NaN
+	let reduce = |value: &dyn Fn(&BodySample) -> f32| -> f32 {
+		let mut values = [0.0f32; N];
+		let mut count = 0;
+		for v in used.iter().map(value).filter(|v| !v.is_nan()) {
+			values[count] = v;
+			count += 1;
+		}
+		let values = &mut values[..count];
+		if values.is_empty() {
+			return f32::NAN;
+		}
+		match fusion {
+			Fusion::Average => values.iter().sum::<f32>() / count as f32,
+			Fusion::Median => {
+				values.sort_unstable_by(f32::total_cmp);
+				match count % 2 {
+					1 => values[count / 2],
+					_ => (values[count / 2 - 1] + values[count / 2]) / 2.0,
+				}
+			},
+		}
+	};
+	Some(BodySample {
+		accel_g: core::array::from_fn(|i| reduce(&|s| s.accel_g[i])),
+		gyro_dps: core::array::from_fn(|i| reduce(&|s| s.gyro_dps[i])),
+		temperature_c: reduce(&|s| s.temperature_c),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn sample(accel_x: f32) -> Option<BodySample> {
+		Some(BodySample { accel_g: [accel_x, 0.0, 1.0], gyro_dps: [0.0; 3], temperature_c: 25.0 })
+	}
+
+	#[test]
+	fn median_outvotes_one_bad_sensor() {
+		let fused = ArraySample::fuse([sample(0.0), sample(0.02), sample(0.5)], Fusion::Median, DisagreementLimits::default());
+		assert_eq!(fused.health, [SensorHealth::Ok, SensorHealth::Ok, SensorHealth::Disagrees]);
+		assert!(!fused.all_ok());
+		assert_eq!(fused.fused.unwrap().accel_g, [0.01, 0.0, 1.0]);
+	}
+
+	#[test]
+	fn two_disagreeing_sensors_give_no_fused_sample() {
+		let fused = ArraySample::fuse([sample(0.0), sample(0.5)], Fusion::Median, DisagreementLimits::default());
+		assert_eq!(fused.health, [SensorHealth::Disagrees; 2]);
+		assert_eq!(fused.fused, None);
+	}
+
+	#[test]
+	fn failed_reads_are_left_out() {
+		let fused = ArraySample::fuse([None, sample(0.0), sample(0.04)], Fusion::Average, DisagreementLimits::default());
+		assert_eq!(fused.health, [SensorHealth::ReadFailed, SensorHealth::Ok, SensorHealth::Ok]);
+		assert_eq!(fused.fused.unwrap().accel_g, [0.02, 0.0, 1.0]);
+
+		let fused = ArraySample::<2>::fuse([None, None], Fusion::Median, DisagreementLimits::default());
+		assert_eq!(fused.health, [SensorHealth::ReadFailed; 2]);
+		assert_eq!(fused.fused, None);
+	}
+
+	#[test]
+	fn disabled_axes_are_skipped() {
+		let mut masked = sample(f32::NAN).unwrap();
+		masked.gyro_dps[2] = f32::NAN;
+		let fused = ArraySample::fuse([Some(masked), sample(0.1), sample(0.2)], Fusion::Average, DisagreementLimits::default());
+		assert!(fused.all_ok());
+		let fused = fused.fused.unwrap();
+		assert!((fused.accel_g[0] - 0.15).abs() < 1e-6);
+		assert_eq!(fused.gyro_dps, [0.0; 3]);
+
+		// an axis nobody measures stays NaN
+		let fused = ArraySample::fuse([Some(masked)], Fusion::Median, DisagreementLimits::default());
+		assert!(fused.fused.unwrap().accel_g[0].is_nan());
+	}
+}
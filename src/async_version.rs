@@ -1,38 +1,298 @@
-// use embedded_hal_async::spi;
-
-// struct SpiSampleDevice<SpiDevice: spi::SpiDevice> {
-// 	spi_device: SpiDevice,
-// }
-
-// impl<SpiDevice: spi::SpiDevice> SpiSampleDevice<SpiDevice> {
-// 	pub fn new(spi_device: SpiDevice) -> Self {
-// 		Self {
-// 			spi_device,
-// 		}
-// 	}
-
-// 	pub async fn read(&mut self, buffer: &mut [u8]) -> Result<(), SpiDevice::Error> {
-// 		// push "hello" to front of buffer
-// 		buffer[0] = b'h';
-// 		buffer[1] = b'e';
-// 		buffer[2] = b'l';
-// 		buffer[3] = b'l';
-// 		buffer[4] = b'o';
-// 		buffer[5] = b'!';
-		
-// 		// read from spi device and append to buffer
-// 		self.spi_device.read(&mut buffer[6..]).await?;
-
-// 		Ok(())
-// 	}
-
-// 	pub async fn write(&mut self, buffer: &[u8]) -> Result<(), SpiDevice::Error> {
-// 		// write buffer to spi device
-// 		self.spi_device.write(buffer).await?;
-
-// 		// write "world" to spi device
-// 		self.spi_device.write(b"world").await?;
-		
-// 		Ok(())
-// 	}
-// }
\ No newline at end of file
+use embedded_hal_async::delay::DelayNs;
+use embedded_hal_async::spi::SpiDevice;
+
+use crate::apex::ApexSettings;
+use crate::clock::ClockInput;
+use crate::data::{AccelData, AxisMask, GyroData, ImuSample, Temperature};
+use crate::error::Error;
+use crate::fifo::{FifoParser, FifoSettings};
+use crate::interface::InterfaceConfig;
+use crate::mounting::Mounting;
+use crate::motion::WomSettings;
+use crate::timestamp::{TickPeriod, Timestamp, TimestampConfig};
+use crate::register::bank0::*;
+use crate::register::bank1::SensorConfig0;
+use crate::register::Constrainer;
+use crate::transport::{BlockingSpiTransport, I3cBus, I3cTransport, SpiInterface, SpiTransport, Transport};
+use crate::type_number::U8;
+
+mod apex;
+mod array;
+mod calibration;
+mod clock;
+mod fifo;
+mod fsync;
+mod interface;
+mod motion;
+mod timestamp;
+
+pub use array::ImuArray;
+pub use motion::{SignificantMotion, WakeOnMotion};
+
+pub struct Icm42688<T> {
+	transport: T,
+	accel_config: (AccelFs, Odr),
+	gyro_config: (GyroFs, Odr),
+	timestamp_config: TimestampConfig,
+	tmstval: Timestamp,
+	fifo_settings: FifoSettings,
+	fifo_parser: FifoParser,
+	clock_input: ClockInput,
+	fsync_ui_sel: FsyncUiSel,
+	wom_settings: Option<WomSettings>,
+	apex: ApexSettings,
+	interface: InterfaceConfig,
+	spi_only: bool,
+	accel_axes: AxisMask,
+	gyro_axes: AxisMask,
+	mounting: Mounting,
+}
+
+impl<D: SpiDevice> Icm42688<SpiTransport<D>> {
+	pub fn new(device: D) -> Self {
+		Self::with_transport(SpiTransport::new(device))
+	}
+}
+
+impl<D: embedded_hal::spi::SpiDevice> Icm42688<BlockingSpiTransport<D>> {
+	// For blocking devices such as embedded-hal-bus' `RefCellDevice` or `CriticalSectionDevice`.
+	// Async devices (`ExclusiveDevice`, mutex based shared devices) go through `new`.
+	pub fn new_blocking(device: D) -> Self {
+		Self::with_transport(BlockingSpiTransport::new(device))
+	}
+}
+
+impl<T: SpiInterface> Icm42688<T> {
+	// Have `init` disable the I2C and I3C interfaces, so other traffic on shared pins can't be taken for a transaction.
+	pub fn lock_to_spi(mut self) -> Self {
+		self.spi_only = true;
+		self
+	}
+}
+
+impl<B: I3cBus> Icm42688<I3cTransport<B>> {
+	// `address` is the dynamic address the controller assigned during bus initialisation.
+	pub fn new_i3c(bus: B, address: u8) -> Self {
+		Self::with_transport(I3cTransport::new(bus, address))
+	}
+}
+
+impl<T: Transport> Icm42688<T> {
+	pub fn with_transport(transport: T) -> Self {
+		Self {
+			transport,
+			// power-on reset values
+			accel_config: (AccelFs::G16, Odr::Hz1k),
+			gyro_config: (GyroFs::Dps2000, Odr::Hz1k),
+			timestamp_config: TimestampConfig::default(),
+			tmstval: Timestamp::tmstval(TickPeriod::new(TimestampConfig::default().resolution, ClockInput::Internal)),
+			fifo_settings: FifoSettings::RESET,
+			fifo_parser: FifoParser::new(TimestampConfig::default(), Odr::Hz1k),
+			clock_input: ClockInput::Internal,
+			fsync_ui_sel: FsyncUiSel::TempOut,
+			wom_settings: None,
+			apex: ApexSettings::RESET,
+			interface: InterfaceConfig::RESET,
+			spi_only: false,
+			accel_axes: AxisMask::ALL,
+			gyro_axes: AxisMask::ALL,
+			mounting: Mounting::IDENTITY,
+		}
+	}
+
+	// Accel and gyro data, including FIFO packets, are reported in the body frame. Survives `init`.
+	// For a new driver; once raise-to-wake runs, the DMP has to be updated through `set_mounting`.
+	pub fn with_mounting(mut self, mounting: Mounting) -> Self {
+		self.mounting = mounting;
+		self.update_fifo_parser();
+		self
+	}
+
+	pub fn release(self) -> T {
+		self.transport
+	}
+
+	pub(crate) fn registers(&mut self) -> Constrainer<'_, T, U8<0>> {
+		Constrainer::bank0(&mut self.transport)
+	}
+
+	// Runs `f` in bank N. Bank 0 is selected again afterwards, also when `f` or the switch fails,
+	// so a bus error can't leave later bank 0 accesses on the wrong registers.
+	pub(crate) async fn with_bank<const N: u8, R>(
+		&mut self,
+		f: impl AsyncFnOnce(&mut Constrainer<'_, T, U8<N>>) -> Result<R, Error<T::Error>>,
+	) -> Result<R, Error<T::Error>> {
+		let result = match self.registers().select_bank::<N>().await {
+			Ok(mut registers) => f(&mut registers).await,
+			Err(e) => Err(e),
+		};
+		let restored = self.registers().select_bank::<0>().await;
+		let value = result?;
+		restored?;
+		Ok(value)
+	}
+
+	// Soft reset and identity check. Leaves both sensors off.
+	pub async fn init(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		// The bank is unknown if the host restarted in the middle of a bank switch.
+		let mut registers = self.registers().select_bank::<0>().await?;
+		registers.write(DeviceConfig::pack(SpiMode::Mode0Mode3, SoftResetConfig::EableReset)).await?;
+		delay.delay_ms(1).await;
+		if self.spi_only {
+			self.disable_i2c_i3c().await?;
+		}
+
+		let who_am_i = self.registers().read::<WhoAmI>().await?;
+		if who_am_i.0 != WhoAmI::ICM42688 {
+			return Err(Error::WhoAmI(who_am_i.0));
+		}
+
+		let (accel_fs, accel_odr) = self.registers().read::<AccelConfig0>().await?.depack();
+		let (gyro_fs, gyro_odr) = self.registers().read::<GyroConfig0>().await?.depack();
+		let (fsync_ui_sel, _, _) = self.registers().read::<FsyncConfig>().await?.depack();
+		self.accel_config = (accel_fs, accel_odr);
+		self.gyro_config = (gyro_fs, gyro_odr);
+		self.timestamp_config = TimestampConfig::default();
+		self.fifo_settings = FifoSettings::RESET;
+		self.clock_input = ClockInput::Internal;
+		self.tmstval = Timestamp::tmstval(self.timestamp_tick());
+		self.fsync_ui_sel = fsync_ui_sel;
+		self.wom_settings = None;
+		self.apex = ApexSettings::RESET;
+		self.interface = InterfaceConfig::RESET;
+		self.accel_axes = AxisMask::ALL;
+		self.gyro_axes = AxisMask::ALL;
+		self.update_fifo_parser();
+		// the device restarted, so there is no time base to continue
+		self.fifo_parser.reset();
+		Ok(())
+	}
+
+	pub async fn set_power(&mut self, gyro_mode: GyroMode, accel_mode: AccelMode, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: PwrMgmt0| {
+			let (temp_dis, idle, _, _) = r.depack();
+			PwrMgmt0::pack(temp_dis, idle, gyro_mode, accel_mode)
+		}).await?;
+		// WoM compares accel samples, there are none with the accel off
+		if matches!(accel_mode, AccelMode::Off | AccelMode::Off01) {
+			self.wom_settings = None;
+		}
+		// no register writes are allowed for 200us after a mode change
+		delay.delay_us(200).await;
+		Ok(())
+	}
+
+	pub async fn configure_accel(&mut self, fs: AccelFs, odr: Odr) -> Result<(), Error<T::Error>> {
+		self.registers().write(AccelConfig0::pack(fs, odr)).await?;
+		self.accel_config = (fs, odr);
+		if let Some(wom) = &mut self.wom_settings {
+			wom.odr = odr;
+		}
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub async fn configure_gyro(&mut self, fs: GyroFs, odr: Odr) -> Result<(), Error<T::Error>> {
+		self.registers().write(GyroConfig0::pack(fs, odr)).await?;
+		self.gyro_config = (fs, odr);
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	// Disabled axes stop being measured to save power, readers report them as `None` through the `*_axes` accessors.
+	pub async fn set_enabled_axes(&mut self, accel: AxisMask, gyro: AxisMask) -> Result<(), Error<T::Error>> {
+		let packed = SensorConfig0::pack(!gyro.z, !gyro.y, !gyro.x, !accel.z, !accel.y, !accel.x);
+		self.with_bank::<1, _>(async |registers| {
+			registers.modify(|r: SensorConfig0| SensorConfig0(r.0 & !SensorConfig0::MASK | packed.0)).await
+		}).await?;
+		self.accel_axes = accel;
+		self.gyro_axes = gyro;
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn enabled_axes(&self) -> (AxisMask, AxisMask) {
+		(self.accel_axes, self.gyro_axes)
+	}
+
+	// Also updates the DMP when raise-to-wake is enabled, which fails with `Error::Mounting` if it can't follow.
+	pub async fn set_mounting(&mut self, mounting: Mounting, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		if self.apex.r2w.is_some() {
+			if mounting.apex_orientation().is_none() {
+				return Err(Error::Mounting);
+			}
+			self.apply_apex_mounted(mounting, delay).await?;
+		}
+		self.mounting = mounting;
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn mounting(&self) -> Mounting {
+		self.mounting
+	}
+
+	pub fn accel_config(&self) -> (AccelFs, Odr) {
+		self.accel_config
+	}
+
+	pub fn gyro_config(&self) -> (GyroFs, Odr) {
+		self.gyro_config
+	}
+
+	pub async fn read_accel(&mut self) -> Result<AccelData, Error<T::Error>> {
+		let mut buf = [0u8; 6];
+		self.registers().read_burst::<AccelDataX>(&mut buf).await?;
+		let raw = decode_xyz(&buf, self.interface.sensor_data_endian);
+		Ok(self.mount_accel(AccelData { raw, fs: self.accel_config.0, axes: self.accel_axes }))
+	}
+
+	pub async fn read_gyro(&mut self) -> Result<GyroData, Error<T::Error>> {
+		let mut buf = [0u8; 6];
+		self.registers().read_burst::<GyroDataX>(&mut buf).await?;
+		let raw = decode_xyz(&buf, self.interface.sensor_data_endian);
+		Ok(self.mount_gyro(GyroData { raw, fs: self.gyro_config.0, axes: self.gyro_axes }))
+	}
+
+	// One burst over TEMP_DATA1..GYRO_DATA_Z0, so all values belong to the same sample.
+	pub async fn read_all(&mut self) -> Result<ImuSample, Error<T::Error>> {
+		let sample = self.read_sensor_frame().await?;
+		Ok(ImuSample { accel: self.mount_accel(sample.accel), gyro: self.mount_gyro(sample.gyro), ..sample })
+	}
+
+	// `read_all` without the mounting, for calibrations that work on the sensor's own axes.
+	pub(crate) async fn read_sensor_frame(&mut self) -> Result<ImuSample, Error<T::Error>> {
+		let mut buf = [0u8; 14];
+		self.registers().read_burst::<TempData>(&mut buf).await?;
+		let accel = [buf[2], buf[3], buf[4], buf[5], buf[6], buf[7]];
+		let gyro = [buf[8], buf[9], buf[10], buf[11], buf[12], buf[13]];
+		let endian = self.interface.sensor_data_endian;
+		Ok(ImuSample {
+			temperature: Temperature { raw: endian.i16([buf[0], buf[1]]) },
+			accel: AccelData { raw: decode_xyz(&accel, endian), fs: self.accel_config.0, axes: self.accel_axes },
+			gyro: GyroData { raw: decode_xyz(&gyro, endian), fs: self.gyro_config.0, axes: self.gyro_axes },
+		})
+	}
+
+	pub async fn read_gyro_with_temperature(&mut self) -> Result<(GyroData, Temperature), Error<T::Error>> {
+		let sample = self.read_all().await?;
+		Ok((sample.gyro, sample.temperature))
+	}
+
+	fn mount_accel(&self, data: AccelData) -> AccelData {
+		AccelData { raw: self.mounting.apply_i16(data.raw), axes: self.mounting.apply_mask(data.axes), ..data }
+	}
+
+	fn mount_gyro(&self, data: GyroData) -> GyroData {
+		GyroData { raw: self.mounting.apply_i16(data.raw), axes: self.mounting.apply_mask(data.axes), ..data }
+	}
+
+	pub async fn read_temperature(&mut self) -> Result<Temperature, Error<T::Error>> {
+		let TempData(bytes) = self.registers().read_word::<TempData>().await?;
+		Ok(Temperature { raw: self.interface.sensor_data_endian.i16(bytes) })
+	}
+}
+
+fn decode_xyz(buf: &[u8; 6], endian: Endian) -> [i16; 3] {
+	core::array::from_fn(|i| endian.i16([buf[2 * i], buf[2 * i + 1]]))
+}
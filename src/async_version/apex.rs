@@ -0,0 +1,196 @@
+use embedded_hal_async::delay::DelayNs;
+
+use crate::apex::{ApexSettings, PedometerData, PedometerSettings, R2wSettings, TapEvent, TapSettings, TiltSettings};
+use crate::error::Error;
+use crate::interrupt::{ApexStatus, InterruptPin};
+use crate::mounting::Mounting;
+use crate::register::bank0::*;
+use crate::register::bank4::*;
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	pub fn apex_settings(&self) -> ApexSettings {
+		self.apex
+	}
+
+	pub async fn configure_dmp(&mut self, odr: DmpOdr, power_save: bool, power_save_time_sel: u8, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.dmp_odr = odr;
+		self.apex.dmp_power_save = power_save;
+		self.apex.dmp_power_save_time_sel = power_save_time_sel;
+		self.apply_apex(delay).await
+	}
+
+	pub async fn set_dmp_sensitivity(&mut self, mode: SensitivityMode, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.sensitivity_mode = mode;
+		self.apply_apex(delay).await
+	}
+
+	pub async fn enable_pedometer(&mut self, settings: PedometerSettings, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.pedometer = Some(settings);
+		self.apply_apex(delay).await
+	}
+
+	pub async fn disable_pedometer(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.pedometer = None;
+		self.apply_apex(delay).await
+	}
+
+	pub async fn enable_tilt_detection(&mut self, settings: TiltSettings, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.tilt = Some(settings);
+		self.apply_apex(delay).await
+	}
+
+	pub async fn disable_tilt_detection(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.tilt = None;
+		self.apply_apex(delay).await
+	}
+
+	// Fails with `Error::Mounting` when the driver's mounting isn't one of the 8 the DMP supports.
+	pub async fn enable_raise_to_wake(&mut self, settings: R2wSettings, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		if self.mounting.apex_orientation().is_none() {
+			return Err(Error::Mounting);
+		}
+		self.apex.r2w = Some(settings);
+		self.apply_apex(delay).await
+	}
+
+	pub async fn disable_raise_to_wake(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.r2w = None;
+		self.apply_apex(delay).await
+	}
+
+	pub async fn enable_tap_detection(&mut self, settings: TapSettings, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.tap = Some(settings);
+		self.apply_apex(delay).await
+	}
+
+	// The accel keeps the low noise mode, ODR and filter settings tap detection needed.
+	pub async fn disable_tap_detection(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apex.tap = None;
+		self.apply_apex(delay).await
+	}
+
+	// Decodes the tap from APEX_DATA4..5, read in one burst, if `status` flags one. Take `status` from
+	// `read_apex_status`, which clears INT_STATUS3, so the other APEX events it carries are not lost.
+	pub async fn tap_event(&mut self, status: &ApexStatus) -> Result<Option<TapEvent>, Error<T::Error>> {
+		if !status.tap {
+			return Ok(None);
+		}
+		let (apex_data4, apex_data5) = self.registers().read_word::<TapData>().await?.depack();
+		Ok(TapEvent::new(apex_data4, apex_data5))
+	}
+
+	// APEX_DATA0..3 in one burst.
+	pub async fn read_pedometer(&mut self) -> Result<PedometerData, Error<T::Error>> {
+		let mut buf = [0u8; 4];
+		self.registers().read_burst::<StepCnt>(&mut buf).await?;
+		let (dmp_idle, activity) = ApexData3(buf[3]).depack();
+		Ok(PedometerData {
+			step_count: StepCnt([buf[0], buf[1]]).depack(),
+			cadence_raw: buf[2],
+			activity,
+			dmp_idle,
+		})
+	}
+
+	// Reads and clears INT_STATUS3.
+	pub async fn read_apex_status(&mut self) -> Result<ApexStatus, Error<T::Error>> {
+		Ok(self.registers().read::<IntStatus3>().await?.into())
+	}
+
+	// Shared DMP initialisation for all APEX features: reset the DMP memory, program the
+	// parameters of every enabled feature, start the DMP and finally enable the features.
+	pub(super) async fn apply_apex(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.apply_apex_mounted(self.mounting, delay).await
+	}
+
+	// `apply_apex` with the DMP told about `mounting` instead of the driver's current one.
+	pub(super) async fn apply_apex_mounted(&mut self, mounting: Mounting, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		let apex = self.apex;
+		self.registers().write(ApexConfig0::pack(false, false, false, false, false, apex.dmp_odr)).await?;
+		if !apex.any_enabled() {
+			return self.write_apex_parameters(&apex, mounting).await;
+		}
+
+		// the DMP needs the accel running at least at its own rate
+		let (accel_fs, accel_odr) = self.accel_config;
+		let dmp_accel_odr = apex.dmp_odr.accel_odr();
+		if accel_odr.period_us() > dmp_accel_odr.period_us() {
+			self.configure_accel(accel_fs, dmp_accel_odr).await?;
+		}
+		// tap works on the UI accel data: low noise at 1 kHz, 3rd order filters, ODR/2 bandwidth
+		if apex.tap.is_some() {
+			let (accel_fs, accel_odr) = self.accel_config;
+			if accel_odr != TapSettings::ACCEL_ODR {
+				self.configure_accel(accel_fs, TapSettings::ACCEL_ODR).await?;
+			}
+			self.registers().modify(|r: AccelConfig1| {
+				AccelConfig1(r.0 & !AccelConfig1::MASK | AccelConfig1::pack(FiltOrd::Third, FiltOrd::Third).0)
+			}).await?;
+			self.registers().modify(|r: GyroAccelConfig0| {
+				let (_, gyro_ui_filt_bw) = r.depack();
+				GyroAccelConfig0::pack(0, gyro_ui_filt_bw)
+			}).await?;
+		}
+		let (temp_dis, idle, gyro_mode, accel_mode) = self.registers().read::<PwrMgmt0>().await?.depack();
+		let apex_accel_mode = if apex.tap.is_some() { AccelMode::LowNoise } else { AccelMode::LowPower };
+		if matches!(accel_mode, AccelMode::Off | AccelMode::Off01) || (apex.tap.is_some() && accel_mode != AccelMode::LowNoise) {
+			self.registers().write(PwrMgmt0::pack(temp_dis, idle, gyro_mode, apex_accel_mode)).await?;
+			delay.delay_us(200).await;
+		}
+
+		self.registers().write(SignalPathReset::pack(false, true, false, false, false)).await?;
+		delay.delay_ms(1).await;
+		self.write_apex_parameters(&apex, mounting).await?;
+		self.registers().write(SignalPathReset::pack(true, false, false, false, false)).await?;
+		delay.delay_ms(50).await;
+
+		self.registers().write(ApexConfig0::pack(
+			apex.dmp_power_save,
+			apex.tap.is_some(),
+			apex.pedometer.is_some(),
+			apex.tilt.is_some(),
+			apex.r2w.is_some(),
+			apex.dmp_odr,
+		)).await
+	}
+
+	async fn write_apex_parameters(&mut self, apex: &ApexSettings, mounting: Mounting) -> Result<(), Error<T::Error>> {
+		let pedometer = apex.pedometer.unwrap_or_default();
+		let step_det = |pin| apex.pedometer.is_some_and(|p| p.pin == Some(pin));
+		let tilt_det = |pin| apex.tilt.is_some_and(|t| t.pin == Some(pin));
+		let r2w_det = |pin| apex.r2w.is_some_and(|r| r.pin == Some(pin));
+		let tap_det = |pin| apex.tap.is_some_and(|t| t.pin == Some(pin));
+		let r2w = apex.r2w.unwrap_or_default();
+		let tap = apex.tap.unwrap_or_default();
+
+		let mounting = mounting.apex_orientation().unwrap_or(MountingOrientation::Identity);
+
+		self.with_bank::<4, _>(async |registers| {
+			registers.write(ApexConfig1::pack(pedometer.low_energy_amp_th_sel, apex.dmp_power_save_time_sel)).await?;
+			registers.write(ApexConfig2::pack(pedometer.amp_th_sel, pedometer.step_cnt_th_sel)).await?;
+			registers.write(ApexConfig3::pack(pedometer.step_det_th_sel, pedometer.sb_timer_th_sel, pedometer.hi_en_th_sel)).await?;
+			let config4 = ApexConfig4::pack(apex.tilt.map_or(TiltWaitTime::S4, |t| t.wait_time), r2w.sleep_time_out);
+			registers.modify(|r: ApexConfig4| ApexConfig4(r.0 & !ApexConfig4::MASK | config4.0)).await?;
+			registers.write(ApexConfig5::pack(mounting)).await?;
+			let config6 = ApexConfig6::pack(r2w.sleep_gesture_delay);
+			registers.modify(|r: ApexConfig6| ApexConfig6(r.0 & !ApexConfig6::MASK | config6.0)).await?;
+			registers.write(ApexConfig7::pack(tap.min_jerk_thr, tap.max_peak_tol)).await?;
+			registers.write(ApexConfig8::pack(tap.tmax, tap.tmin, tap.smudge_reject_thr)).await?;
+			let config9 = ApexConfig9::pack(apex.sensitivity_mode);
+			registers.modify(|r: ApexConfig9| ApexConfig9(r.0 & !ApexConfig9::MASK | config9.0)).await?;
+			registers.modify(|r: IntSource6| {
+				let (_, step_cnt_ofl, _, _, _, _) = r.depack();
+				let (int1, r2w1) = (InterruptPin::Int1, r2w_det(InterruptPin::Int1));
+				IntSource6::pack(step_det(int1), step_cnt_ofl, tilt_det(int1), r2w1, r2w1, tap_det(int1))
+			}).await?;
+			registers.modify(|r: IntSource7| {
+				let (_, step_cnt_ofl, _, _, _, _) = r.depack();
+				let (int2, r2w2) = (InterruptPin::Int2, r2w_det(InterruptPin::Int2));
+				IntSource7::pack(step_det(int2), step_cnt_ofl, tilt_det(int2), r2w2, r2w2, tap_det(int2))
+			}).await
+		}).await
+	}
+}
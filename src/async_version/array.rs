@@ -0,0 +1,76 @@
+use embedded_hal_async::delay::DelayNs;
+
+use crate::array::{ArrayConfig, ArraySample, BodySample, DisagreementLimits, Fusion};
+use crate::error::Error;
+use crate::mounting::Mounting;
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+// Two or more redundant sensors read in lockstep and fused into one body frame sample.
+pub struct ImuArray<T, const N: usize> {
+	imus: [Icm42688<T>; N],
+	fusion: Fusion,
+	limits: DisagreementLimits,
+}
+
+impl<T: Transport, const N: usize> ImuArray<T, N> {
+	// Samples come out in the body frame through each sensor's own mounting, voted by median.
+	pub fn new(imus: [Icm42688<T>; N]) -> Self {
+		Self { imus, fusion: Fusion::Median, limits: DisagreementLimits::default() }
+	}
+
+	// Per sensor `set_mounting`, every sensor is updated even if an earlier one fails.
+	pub async fn set_mountings(&mut self, mountings: [Mounting; N], delay: &mut impl DelayNs) -> [Result<(), Error<T::Error>>; N] {
+		let mut results = [const { Ok(()) }; N];
+		for ((imu, mounting), result) in self.imus.iter_mut().zip(mountings).zip(results.iter_mut()) {
+			*result = imu.set_mounting(mounting, delay).await;
+		}
+		results
+	}
+
+	pub fn with_fusion(mut self, fusion: Fusion, limits: DisagreementLimits) -> Self {
+		self.fusion = fusion;
+		self.limits = limits;
+		self
+	}
+
+	pub fn sensor(&mut self, i: usize) -> &mut Icm42688<T> {
+		&mut self.imus[i]
+	}
+
+	pub fn release(self) -> [Icm42688<T>; N] {
+		self.imus
+	}
+
+	// Every sensor is brought up even if an earlier one fails, the result is per sensor.
+	pub async fn init(&mut self, config: &ArrayConfig, delay: &mut impl DelayNs) -> [Result<(), Error<T::Error>>; N] {
+		let mut results = [const { Ok(()) }; N];
+		for (imu, result) in self.imus.iter_mut().zip(results.iter_mut()) {
+			*result = Self::init_one(imu, config, delay).await;
+		}
+		results
+	}
+
+	// One burst per sensor, back to back, so the samples are at most one transaction apart.
+	pub async fn read(&mut self) -> ArraySample<N> {
+		let mut sensors = [None; N];
+		for (imu, sensor) in self.imus.iter_mut().zip(sensors.iter_mut()) {
+			if let Ok(sample) = imu.read_all().await {
+				*sensor = Some(BodySample {
+					accel_g: sample.accel.g(),
+					gyro_dps: sample.gyro.dps(),
+					temperature_c: sample.temperature.celsius(),
+				});
+			}
+		}
+		ArraySample::fuse(sensors, self.fusion, self.limits)
+	}
+
+	async fn init_one(imu: &mut Icm42688<T>, config: &ArrayConfig, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		imu.init(delay).await?;
+		imu.configure_accel(config.accel_fs, config.accel_odr).await?;
+		imu.configure_gyro(config.gyro_fs, config.gyro_odr).await?;
+		imu.set_power(config.gyro_mode, config.accel_mode, delay).await
+	}
+}
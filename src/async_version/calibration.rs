@@ -0,0 +1,127 @@
+use embedded_hal_async::delay::DelayNs;
+
+use crate::calibration::{Calibration, GravityAxis, GyroThermalFit, RestAccumulator, SixPositionCalibrator};
+use crate::data::{AxisMask, ImuSample};
+use crate::error::Error;
+use crate::register::bank0::*;
+use crate::register::bank4::{depack_offset_user, pack_offset_user, OffsetUser};
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+// Known configuration used while averaging: the finest ranges, 1 kHz.
+const REST_ACCEL_FS: AccelFs = AccelFs::G2;
+const REST_GYRO_FS: GyroFs = GyroFs::Dps250;
+const REST_ODR: Odr = Odr::Hz1k;
+const REST_SETTLE_MS: u32 = 50;
+
+impl<T: Transport> Icm42688<T> {
+	// Averages `samples` readings while the device is stationary, with `gravity` pointing along the given axis.
+	// Both `gravity` and the result are in the sensor frame, whatever the mounting.
+	pub async fn calibrate_at_rest(&mut self, samples: u16, gravity: GravityAxis, delay: &mut impl DelayNs) -> Result<Calibration, Error<T::Error>> {
+		self.average_at_rest(samples, delay).await?.finish(gravity).ok_or(Error::Motion)
+	}
+
+	// Averaged sensor frame reading of one face for `SixPositionCalibrator`, `up` is a sensor axis.
+	pub async fn collect_face(&mut self, calibrator: &mut SixPositionCalibrator, up: GravityAxis, samples: u16, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		let accumulator = self.average_at_rest(samples, delay).await?;
+		if !accumulator.is_at_rest() {
+			return Err(Error::Motion);
+		}
+		calibrator.add(up, accumulator.accel_mean_g());
+		Ok(())
+	}
+
+	// Adds one (temperature, bias) point averaged over `samples` readings at the current configuration.
+	// The device must be at rest with the gyro running; call this repeatedly while it warms up.
+	// Disabled gyro axes are left out of the fit.
+	pub async fn record_gyro_thermal_point<const N: usize>(&mut self, fit: &mut GyroThermalFit<N>, samples: u16, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		if samples == 0 {
+			return Ok(());
+		}
+		let mut temperature_sum = 0.0;
+		let mut bias_sum = [0.0; 3];
+		for _ in 0..samples {
+			let ImuSample { gyro, temperature, .. } = self.read_sensor_frame().await?;
+			let dps = gyro.dps();
+			temperature_sum += temperature.celsius();
+			for i in 0..3 {
+				bias_sum[i] += dps[i];
+			}
+			delay.delay_us(self.gyro_config.1.period_us() as u32).await;
+		}
+		let n = samples as f32;
+		fit.push(temperature_sum / n, bias_sum.map(|v| v / n));
+		Ok(())
+	}
+
+	// Samples every axis at a known range and ODR. The previous range, ODR, enabled axes and power configuration
+	// are restored afterwards, also when the run fails.
+	pub async fn average_at_rest(&mut self, samples: u16, delay: &mut impl DelayNs) -> Result<RestAccumulator, Error<T::Error>> {
+		let accel_config = self.accel_config;
+		let gyro_config = self.gyro_config;
+		let axes = self.enabled_axes();
+		let pwr_mgmt0 = self.registers().read::<PwrMgmt0>().await?;
+
+		let result = async {
+			self.set_enabled_axes(AxisMask::ALL, AxisMask::ALL).await?;
+			self.configure_accel(REST_ACCEL_FS, REST_ODR).await?;
+			self.configure_gyro(REST_GYRO_FS, REST_ODR).await?;
+			self.set_power(GyroMode::LowNoise, AccelMode::LowNoise, delay).await?;
+			delay.delay_ms(REST_SETTLE_MS).await;
+
+			let mut accumulator = RestAccumulator::new();
+			for _ in 0..samples {
+				let sample = self.read_sensor_frame().await?;
+				accumulator.push(sample.accel.g(), sample.gyro.dps());
+				delay.delay_us(REST_ODR.period_us() as u32).await;
+			}
+			Ok(accumulator)
+		}.await;
+
+		let restored = self.restore_rest_config(pwr_mgmt0, accel_config, gyro_config, axes, delay).await;
+		let accumulator = result?;
+		restored?;
+		Ok(accumulator)
+	}
+
+	async fn restore_rest_config(&mut self, pwr_mgmt0: PwrMgmt0, accel_config: (AccelFs, Odr), gyro_config: (GyroFs, Odr), axes: (AxisMask, AxisMask), delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		self.registers().write(pwr_mgmt0).await?;
+		delay.delay_us(200).await;
+		self.set_enabled_axes(axes.0, axes.1).await?;
+		self.configure_accel(accel_config.0, accel_config.1).await?;
+		self.configure_gyro(gyro_config.0, gyro_config.1).await
+	}
+
+	// Adds the correction of sensor frame `calibration` to whatever OFFSET_USER currently holds.
+	pub async fn write_offset_user(&mut self, calibration: &Calibration) -> Result<(), Error<T::Error>> {
+		self.with_bank::<4, _>(async |registers| {
+			let current = [
+				registers.read::<OffsetUser<0>>().await?.0,
+				registers.read::<OffsetUser<1>>().await?.0,
+				registers.read::<OffsetUser<2>>().await?.0,
+				registers.read::<OffsetUser<3>>().await?.0,
+				registers.read::<OffsetUser<4>>().await?.0,
+				registers.read::<OffsetUser<5>>().await?.0,
+				registers.read::<OffsetUser<6>>().await?.0,
+				registers.read::<OffsetUser<7>>().await?.0,
+				registers.read::<OffsetUser<8>>().await?.0,
+			];
+			let (gyro, accel) = depack_offset_user(current);
+			let (gyro_correction, accel_correction) = calibration.offset_user_lsb();
+			let bytes = pack_offset_user(
+				core::array::from_fn(|i| gyro[i].saturating_add(gyro_correction[i])),
+				core::array::from_fn(|i| accel[i].saturating_add(accel_correction[i])),
+			);
+			registers.write(OffsetUser::<0>(bytes[0])).await?;
+			registers.write(OffsetUser::<1>(bytes[1])).await?;
+			registers.write(OffsetUser::<2>(bytes[2])).await?;
+			registers.write(OffsetUser::<3>(bytes[3])).await?;
+			registers.write(OffsetUser::<4>(bytes[4])).await?;
+			registers.write(OffsetUser::<5>(bytes[5])).await?;
+			registers.write(OffsetUser::<6>(bytes[6])).await?;
+			registers.write(OffsetUser::<7>(bytes[7])).await?;
+			registers.write(OffsetUser::<8>(bytes[8])).await
+		}).await
+	}
+}
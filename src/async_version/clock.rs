@@ -0,0 +1,62 @@
+use crate::clock::{ClockInput, CLKIN_RANGE_HZ};
+use crate::error::Error;
+use crate::register::bank0::{ClkSel, IntfConfig1, Odr};
+use crate::register::bank1::{IntfConfig5, Pin9Function};
+use crate::timestamp::Timestamp;
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	pub async fn set_clock_source(&mut self, clksel: ClkSel) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: IntfConfig1| {
+			let (accel_lp_clk_sel, rtc_mode, _) = r.depack();
+			IntfConfig1(r.0 & !IntfConfig1::MASK | IntfConfig1::pack(accel_lp_clk_sel, rtc_mode, clksel).0)
+		}).await
+	}
+
+	// Switches pin 9 to CLKIN and enables RTC mode. ODRs are then derived from the `frequency_hz` clock,
+	// which must be within `CLKIN_RANGE_HZ`.
+	pub async fn enable_clkin(&mut self, frequency_hz: u32) -> Result<(), Error<T::Error>> {
+		if !CLKIN_RANGE_HZ.contains(&frequency_hz) {
+			return Err(Error::ClkinFrequency(frequency_hz));
+		}
+		self.set_pin9_function(Pin9Function::Clkin).await?;
+		self.registers().modify(|r: IntfConfig1| {
+			let (accel_lp_clk_sel, _, clksel) = r.depack();
+			IntfConfig1(r.0 & !IntfConfig1::MASK | IntfConfig1::pack(accel_lp_clk_sel, true, clksel).0)
+		}).await?;
+		self.clock_input = ClockInput::Clkin(frequency_hz);
+		self.tmstval = Timestamp::tmstval(self.timestamp_tick());
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	// Back to the internal oscillator. Pin 9 returns to INT2.
+	pub async fn disable_clkin(&mut self) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: IntfConfig1| {
+			let (accel_lp_clk_sel, _, clksel) = r.depack();
+			IntfConfig1(r.0 & !IntfConfig1::MASK | IntfConfig1::pack(accel_lp_clk_sel, false, clksel).0)
+		}).await?;
+		self.set_pin9_function(Pin9Function::Int2).await?;
+		self.clock_input = ClockInput::Internal;
+		self.tmstval = Timestamp::tmstval(self.timestamp_tick());
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn clock_input(&self) -> ClockInput {
+		self.clock_input
+	}
+
+	// Real output data rate of `odr` with the current clock input.
+	pub fn odr_hz(&self, odr: Odr) -> f32 {
+		self.clock_input.odr_hz(odr)
+	}
+
+	pub(super) async fn set_pin9_function(&mut self, function: Pin9Function) -> Result<(), Error<T::Error>> {
+		self.with_bank::<1, _>(async |registers| {
+			registers.modify(|r: IntfConfig5| IntfConfig5(r.0 & !IntfConfig5::MASK | IntfConfig5::pack(function).0)).await
+		}).await
+	}
+}
@@ -0,0 +1,97 @@
+use crate::error::Error;
+use crate::fifo::{FifoPackets, FifoParser, FifoSettings};
+use crate::register::bank0::*;
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	pub async fn configure_fifo(&mut self, settings: FifoSettings) -> Result<(), Error<T::Error>> {
+		let mut registers = self.registers();
+		registers.write(FifoConfig::pack(FifoMode::Bypass)).await?;
+		registers.write(FifoConfig1::pack(
+			false,
+			false,
+			settings.hires,
+			settings.timestamp_fsync,
+			settings.temperature,
+			settings.gyro,
+			settings.accel,
+		)).await?;
+		registers.write(FifoConfig2(settings.watermark as u8)).await?;
+		registers.write(FifoConfig3((settings.watermark >> 8) as u8 & 0x0F)).await?;
+		registers.write(FifoConfig::pack(settings.mode)).await?;
+		self.fifo_settings = settings;
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn fifo_settings(&self) -> FifoSettings {
+		self.fifo_settings
+	}
+
+	pub async fn flush_fifo(&mut self) -> Result<(), Error<T::Error>> {
+		self.registers().write(SignalPathReset::pack(false, false, false, false, true)).await?;
+		self.fifo_parser.reset();
+		Ok(())
+	}
+
+	// Bytes, or packets when `InterfaceConfig::fifo_count_records` is set.
+	pub async fn read_fifo_count(&mut self) -> Result<u16, Error<T::Error>> {
+		let FifoCount(bytes) = self.registers().read_word::<FifoCount>().await?;
+		Ok(self.interface.fifo_count_endian.u16(bytes))
+	}
+
+	// Drains as many whole packets as fit in `buf` and parses them, with an absolute time for each.
+	pub async fn read_fifo<'a>(&'a mut self, buf: &'a mut [u8]) -> Result<FifoPackets<'a>, Error<T::Error>> {
+		let len = self.fifo_read_len(buf.len()).await?;
+		if len == 0 {
+			return Ok(self.fifo_parser.parse(&[]));
+		}
+		self.registers().read_multi::<FifoData>(&mut buf[..len]).await?;
+		Ok(self.fifo_parser.parse(&buf[..len]))
+	}
+
+	// Zero-copy variant of `read_fifo` for DMA capable buses: one in-place transfer over `buf`, whose
+	// first byte carries the address. The returned packets borrow `buf[1..]`.
+	pub async fn read_fifo_in_place<'a>(&'a mut self, buf: &'a mut [u8]) -> Result<FifoPackets<'a>, Error<T::Error>> {
+		// no room for data, or nothing to read: skip the transfer
+		if buf.len() < 2 {
+			return Ok(self.fifo_parser.parse(&[]));
+		}
+		let len = self.fifo_read_len(buf.len() - 1).await?;
+		if len == 0 {
+			return Ok(self.fifo_parser.parse(&[]));
+		}
+		self.registers().read_multi_in_place::<FifoData>(&mut buf[..len + 1]).await?;
+		Ok(self.fifo_parser.parse(&buf[1..len + 1]))
+	}
+
+	// Whole packets available in the FIFO, limited to `capacity` bytes.
+	async fn fifo_read_len(&mut self, capacity: usize) -> Result<usize, Error<T::Error>> {
+		let packet_len = self.fifo_settings.packet_len().max(1);
+		let count = match self.read_fifo_count().await? as usize {
+			records if self.interface.fifo_count_records => records * packet_len,
+			bytes => bytes,
+		};
+		Ok(count.min(capacity) / packet_len * packet_len)
+	}
+
+	// Packets are written at the faster of the enabled sensors' ODR. The time base carries over.
+	pub(super) fn update_fifo_parser(&mut self) {
+		let accel = self.fifo_settings.accel.then_some(self.accel_config.1);
+		let gyro = self.fifo_settings.gyro.then_some(self.gyro_config.1);
+		let odr = match (accel, gyro) {
+			(Some(a), Some(g)) if g.period_us() < a.period_us() => g,
+			(Some(a), _) => a,
+			(None, Some(g)) => g,
+			(None, None) => self.accel_config.1,
+		};
+		self.fifo_parser = FifoParser::new(self.timestamp_config, odr)
+			.with_clock(self.clock_input)
+			.with_endian(self.interface.sensor_data_endian)
+			.with_axes(self.accel_axes, self.gyro_axes)
+			.with_mounting(self.mounting)
+			.continuing(&self.fifo_parser);
+	}
+}
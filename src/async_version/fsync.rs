@@ -0,0 +1,45 @@
+use crate::error::Error;
+use crate::register::bank0::{Endian, FsyncConfig, FsyncPolarity, FsyncUiFlagClearSel, FsyncUiSel, TempData};
+use crate::register::Address;
+use crate::register::bank1::Pin9Function;
+use crate::timestamp::{FsyncSettings, TimestampConfig};
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	// Routes pin 9 to FSYNC, tags `settings.ui_sel` and captures the FSYNC time in TMST_FSYNC and the FIFO.
+	pub async fn enable_fsync(&mut self, settings: FsyncSettings) -> Result<(), Error<T::Error>> {
+		self.set_pin9_function(Pin9Function::Fsync).await?;
+		self.registers().write(FsyncConfig::pack(settings.ui_sel, settings.flag_clear, settings.polarity)).await?;
+		self.configure_timestamp(TimestampConfig { fsync_capture: true, ..self.timestamp_config }).await?;
+		self.fsync_ui_sel = settings.ui_sel;
+		Ok(())
+	}
+
+	pub async fn disable_fsync(&mut self) -> Result<(), Error<T::Error>> {
+		self.registers().write(FsyncConfig::pack(FsyncUiSel::None, FsyncUiFlagClearSel::OnUpdate, FsyncPolarity::RisingEdge)).await?;
+		self.configure_timestamp(TimestampConfig { fsync_capture: false, ..self.timestamp_config }).await?;
+		self.set_pin9_function(Pin9Function::Int2).await?;
+		self.fsync_ui_sel = FsyncUiSel::None;
+		Ok(())
+	}
+
+	// Time from the last FSYNC edge to the tagged sample in microseconds, if the tagged register carries the flag.
+	// TEMP_DATA1 .. TMST_FSYNCL are read in one burst so the flag and the delta belong together.
+	pub async fn read_fsync_event(&mut self) -> Result<Option<u16>, Error<T::Error>> {
+		let Some(addr) = self.fsync_ui_sel.data_addr() else {
+			return Ok(None);
+		};
+		let mut buf = [0u8; 16];
+		self.registers().read_burst::<TempData>(&mut buf).await?;
+		let endian = self.interface.sensor_data_endian;
+		let high = (addr - <TempData as Address<0>>::ADDR) as usize;
+		let low = if endian == Endian::Big { high + 1 } else { high };
+		if buf[low] & 0b1 == 0 {
+			return Ok(None);
+		}
+		let ticks = endian.u16([buf[14], buf[15]]) as u64;
+		Ok(Some(self.timestamp_tick().ticks_to_us(ticks).min(u16::MAX as u64) as u16))
+	}
+}
@@ -0,0 +1,71 @@
+use crate::error::Error;
+use crate::interface::{I3cSettings, InterfaceConfig};
+use crate::register::bank0::{DriveConfig, IntfConfig0, SlewRate, UiSifsCfg};
+use crate::register::bank1::{IntfConfig4, IntfConfig6, SpiWire};
+use crate::transport::{I3cBus, I3cTransport, Transport};
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	// Every read after this decodes with the new byte order and count format.
+	pub async fn configure_interface(&mut self, config: InterfaceConfig) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: IntfConfig0| {
+			let (_, _, _, _, ui_sifs_cfg) = r.depack();
+			let packed = IntfConfig0::pack(
+				config.fifo_hold_last_data,
+				config.fifo_count_records,
+				config.fifo_count_endian,
+				config.sensor_data_endian,
+				ui_sifs_cfg,
+			);
+			IntfConfig0(r.0 & !IntfConfig0::MASK | packed.0)
+		}).await?;
+		self.interface = config;
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn interface_config(&self) -> InterfaceConfig {
+		self.interface
+	}
+
+	// The SPI bus itself has to be set up for the same mode, 3-wire shares SDI for both directions.
+	pub async fn set_spi_wire(&mut self, wire: SpiWire) -> Result<(), Error<T::Error>> {
+		self.with_bank::<1, _>(async |registers| {
+			registers.modify(|r: IntfConfig4| IntfConfig4(r.0 & !IntfConfig4::MASK | IntfConfig4::pack(wire).0)).await
+		}).await
+	}
+
+	pub async fn set_slew_rate(&mut self, i2c: SlewRate, spi: SlewRate) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: DriveConfig| DriveConfig(r.0 & !DriveConfig::MASK | DriveConfig::pack(i2c, spi).0)).await
+	}
+
+	// Soft reset enables the other interfaces again, `init` repeats this after it with `lock_to_spi`.
+	// Only reachable through `lock_to_spi`, disabling I3C from an I3C transport would cut it off.
+	pub(crate) async fn disable_i2c_i3c(&mut self) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: IntfConfig0| {
+			let (hold_last_data, count_rec, count_endian, data_endian, _) = r.depack();
+			let packed = IntfConfig0::pack(hold_last_data, count_rec, count_endian, data_endian, UiSifsCfg::DisableI2c);
+			IntfConfig0(r.0 & !IntfConfig0::MASK | packed.0)
+		}).await?;
+		self.with_bank::<1, _>(async |registers| {
+			registers.modify(|r: IntfConfig6| IntfConfig6(r.0 & !IntfConfig6::MASK | IntfConfig6::pack(false, false, false, false, false).0)).await
+		}).await
+	}
+
+	pub async fn configure_i3c(&mut self, settings: I3cSettings) -> Result<(), Error<T::Error>> {
+		self.with_bank::<1, _>(async |registers| {
+			registers.modify(|r: IntfConfig6| {
+				let packed = IntfConfig6::pack(true, settings.ibi_payload, settings.ibi, settings.ddr, true);
+				IntfConfig6(r.0 & !IntfConfig6::MASK | packed.0)
+			}).await
+		}).await
+	}
+}
+
+impl<B: I3cBus> Icm42688<I3cTransport<B>> {
+	// Interrupts routed to INT1 are signalled in band instead.
+	pub async fn wait_for_ibi(&mut self) -> Result<Option<u8>, Error<B::Error>> {
+		self.transport.wait_for_ibi().await.map_err(Error::Transport)
+	}
+}
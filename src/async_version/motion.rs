@@ -0,0 +1,152 @@
+use embedded_hal_async::delay::DelayNs;
+
+use crate::error::Error;
+use crate::interrupt::{InterruptPin, MotionStatus};
+use crate::motion::{wom_threshold_lsb, SmdWindow, WomSettings};
+use crate::register::bank0::*;
+use crate::register::bank4::{AccelWomXThr, AccelWomYThr, AccelWomZThr};
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+// Proof that the WoM thresholds are programmed. Significant motion detection can only be built from here.
+pub struct WakeOnMotion<'a, T> {
+	imu: &'a mut Icm42688<T>,
+}
+
+// SMD on top of WoM, with its interrupt routed.
+pub struct SignificantMotion<'a, T> {
+	imu: &'a mut Icm42688<T>,
+}
+
+impl<T: Transport> Icm42688<T> {
+	// Accel in low power mode at `odr`, gyro off. Fails with `Error::LowPowerOdr` unless `odr.low_power()`.
+	pub async fn enable_low_power_accel(&mut self, odr: Odr, delay: &mut impl DelayNs) -> Result<(), Error<T::Error>> {
+		if !odr.low_power() {
+			return Err(Error::LowPowerOdr(odr));
+		}
+		self.configure_accel(self.accel_config.0, odr).await?;
+		self.set_power(GyroMode::Off, AccelMode::LowPower, delay).await
+	}
+
+	pub async fn enable_wake_on_motion(&mut self, threshold_mg: u16, mode: WomMode, delay: &mut impl DelayNs) -> Result<WakeOnMotion<'_, T>, Error<T::Error>> {
+		self.configure_wake_on_motion(WomSettings::new(threshold_mg, mode), delay).await
+	}
+
+	// Follows the WoM programming sequence of the datasheet.
+	pub async fn configure_wake_on_motion(&mut self, settings: WomSettings, delay: &mut impl DelayNs) -> Result<WakeOnMotion<'_, T>, Error<T::Error>> {
+		if !settings.odr.low_power() {
+			return Err(Error::LowPowerOdr(settings.odr));
+		}
+		self.registers().modify(|r: IntfConfig1| {
+			let (_, rtc_mode, clksel) = r.depack();
+			IntfConfig1(r.0 & !IntfConfig1::MASK | IntfConfig1::pack(settings.lp_clock, rtc_mode, clksel).0)
+		}).await?;
+		self.enable_low_power_accel(settings.odr, delay).await?;
+		delay.delay_ms(1).await;
+
+		let [x, y, z] = settings.thresholds_mg.map(wom_threshold_lsb);
+		self.with_bank::<4, _>(async |registers| {
+			registers.write(AccelWomXThr(x)).await?;
+			registers.write(AccelWomYThr(y)).await?;
+			registers.write(AccelWomZThr(z)).await
+		}).await?;
+		delay.delay_ms(1).await;
+
+		self.route_wom_interrupt(Some(settings.pin)).await?;
+		delay.delay_ms(50).await;
+
+		// significant motion has to be enabled again through the returned handle
+		self.route_smd_interrupt(None).await?;
+		self.registers().write(SmdConfig::pack(settings.int_mode, settings.mode, SmdMode::Wom)).await?;
+		self.wom_settings = Some(settings);
+		Ok(WakeOnMotion { imu: self })
+	}
+
+	// Handle for WoM configured earlier, None if it is not running. Turning the accel off stops WoM,
+	// a new accel ODR is taken over into `WomSettings::odr`.
+	pub fn wake_on_motion(&mut self) -> Option<WakeOnMotion<'_, T>> {
+		self.wom_settings.map(|_| WakeOnMotion { imu: self })
+	}
+
+	pub async fn disable_wake_on_motion(&mut self) -> Result<(), Error<T::Error>> {
+		self.registers().modify(|r: SmdConfig| {
+			let (wom_int_mode, wom_mode, _) = r.depack();
+			SmdConfig::pack(wom_int_mode, wom_mode, SmdMode::Disabled)
+		}).await?;
+		self.route_wom_interrupt(None).await?;
+		self.route_smd_interrupt(None).await?;
+		self.wom_settings = None;
+		Ok(())
+	}
+
+	pub fn wom_settings(&self) -> Option<WomSettings> {
+		self.wom_settings
+	}
+
+	// Reads and clears INT_STATUS2.
+	pub async fn read_motion_status(&mut self) -> Result<MotionStatus, Error<T::Error>> {
+		Ok(self.registers().read::<IntStatus2>().await?.into())
+	}
+
+	async fn route_wom_interrupt(&mut self, pin: Option<InterruptPin>) -> Result<(), Error<T::Error>> {
+		let (int1, int2) = (pin == Some(InterruptPin::Int1), pin == Some(InterruptPin::Int2));
+		self.registers().modify(|r: IntSource1| {
+			let (i3c_protocol_error, smd, _, _, _) = r.depack();
+			IntSource1::pack(i3c_protocol_error, smd, int1, int1, int1)
+		}).await?;
+		self.registers().modify(|r: IntSource4| {
+			let (i3c_protocol_error, smd, _, _, _) = r.depack();
+			IntSource4::pack(i3c_protocol_error, smd, int2, int2, int2)
+		}).await
+	}
+
+	async fn route_smd_interrupt(&mut self, pin: Option<InterruptPin>) -> Result<(), Error<T::Error>> {
+		let (int1, int2) = (pin == Some(InterruptPin::Int1), pin == Some(InterruptPin::Int2));
+		self.registers().modify(|r: IntSource1| {
+			let (i3c_protocol_error, _, wom_z, wom_y, wom_x) = r.depack();
+			IntSource1::pack(i3c_protocol_error, int1, wom_z, wom_y, wom_x)
+		}).await?;
+		self.registers().modify(|r: IntSource4| {
+			let (i3c_protocol_error, _, wom_z, wom_y, wom_x) = r.depack();
+			IntSource4::pack(i3c_protocol_error, int2, wom_z, wom_y, wom_x)
+		}).await
+	}
+}
+
+impl<'a, T: Transport> WakeOnMotion<'a, T> {
+	pub fn settings(&self) -> WomSettings {
+		// a handle only exists while WoM is configured
+		self.imu.wom_settings.unwrap()
+	}
+
+	pub async fn read_status(&mut self) -> Result<MotionStatus, Error<T::Error>> {
+		self.imu.read_motion_status().await
+	}
+
+	pub async fn enable_significant_motion(self, window: SmdWindow, pin: InterruptPin) -> Result<SignificantMotion<'a, T>, Error<T::Error>> {
+		self.imu.route_smd_interrupt(Some(pin)).await?;
+		self.imu.registers().modify(|r: SmdConfig| {
+			let (wom_int_mode, wom_mode, _) = r.depack();
+			SmdConfig::pack(wom_int_mode, wom_mode, window.into())
+		}).await?;
+		Ok(SignificantMotion { imu: self.imu })
+	}
+}
+
+impl<'a, T: Transport> SignificantMotion<'a, T> {
+	// Reads and clears INT_STATUS2, `smd` is set once a significant motion was detected.
+	pub async fn read_status(&mut self) -> Result<MotionStatus, Error<T::Error>> {
+		self.imu.read_motion_status().await
+	}
+
+	// Back to plain WoM.
+	pub async fn disable(self) -> Result<WakeOnMotion<'a, T>, Error<T::Error>> {
+		self.imu.route_smd_interrupt(None).await?;
+		self.imu.registers().modify(|r: SmdConfig| {
+			let (wom_int_mode, wom_mode, _) = r.depack();
+			SmdConfig::pack(wom_int_mode, wom_mode, SmdMode::Wom)
+		}).await?;
+		Ok(WakeOnMotion { imu: self.imu })
+	}
+}
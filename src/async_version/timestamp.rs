@@ -0,0 +1,52 @@
+use crate::error::Error;
+use crate::register::bank0::{SignalPathReset, TmstConfig, TmstFsync};
+use crate::register::bank1::{depack_tmstval, TmstVal0, TmstVal1, TmstVal2};
+use crate::timestamp::{TickPeriod, Timestamp, TimestampConfig};
+use crate::transport::Transport;
+
+use super::Icm42688;
+
+impl<T: Transport> Icm42688<T> {
+	pub async fn configure_timestamp(&mut self, config: TimestampConfig) -> Result<(), Error<T::Error>> {
+		let packed = TmstConfig::pack(config.to_registers, config.resolution, config.delta, config.fsync_capture, config.enable);
+		self.registers().modify(|r: TmstConfig| TmstConfig(r.0 & !TmstConfig::MASK | packed.0)).await?;
+		self.timestamp_config = config;
+		self.tmstval = Timestamp::tmstval(self.timestamp_tick());
+		self.update_fifo_parser();
+		Ok(())
+	}
+
+	pub fn timestamp_config(&self) -> TimestampConfig {
+		self.timestamp_config
+	}
+
+	// Length of one TMSTVAL / FIFO timestamp / TMST_FSYNC tick with the current resolution and clock input.
+	pub fn timestamp_tick(&self) -> TickPeriod {
+		TickPeriod::new(self.timestamp_config.resolution, self.clock_input)
+	}
+
+	// Latches the free running counter with TMST_STROBE and returns the raw 20 bit TMSTVAL.
+	// Requires `TimestampConfig::to_registers`.
+	pub async fn read_tmstval(&mut self) -> Result<u32, Error<T::Error>> {
+		self.registers().write(SignalPathReset::pack(false, false, false, true, false)).await?;
+		self.with_bank::<1, _>(async |registers| {
+			let val0 = registers.read::<TmstVal0>().await?;
+			let val1 = registers.read::<TmstVal1>().await?;
+			let val2 = registers.read::<TmstVal2>().await?;
+			Ok(depack_tmstval(val0, val1, val2))
+		}).await
+	}
+
+	// TMSTVAL unwrapped into microseconds. The count starts from the counter value at the first call, not from 0.
+	// Must be called at least once per `Timestamp::rollover_us` (about 1 s at 1 us resolution).
+	pub async fn read_timestamp_us(&mut self) -> Result<u64, Error<T::Error>> {
+		let raw = self.read_tmstval().await?;
+		Ok(self.tmstval.update(raw))
+	}
+
+	// Time of the last FSYNC edge relative to the following ODR event, in timestamp ticks.
+	pub async fn read_tmst_fsync(&mut self) -> Result<u16, Error<T::Error>> {
+		let TmstFsync(bytes) = self.registers().read_word::<TmstFsync>().await?;
+		Ok(self.interface.sensor_data_endian.u16(bytes))
+	}
+}
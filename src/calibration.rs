@@ -0,0 +1,241 @@
+use crate::data::{AccelData, GyroData};
+use crate::mounting::Mounting;
+
+mod least_squares;
+mod six_position;
+mod thermal;
+
+pub use least_squares::LeastSquares;
+pub use six_position::{AccelCalibration, SixPositionCalibrator};
+pub use thermal::{GyroThermalFit, GyroThermalModel};
+
+// A run is rejected as "moving" when the standard deviation of any axis exceeds these limits.
+pub const MAX_REST_ACCEL_STD_G: f32 = 0.01;
+pub const MAX_REST_GYRO_STD_DPS: f32 = 0.5;
+
+// The axis (and its sign) that reads +1 g while the device is at rest.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum GravityAxis {
+	PosX,
+	NegX,
+	PosY,
+	NegY,
+	PosZ,
+	NegZ,
+}
+
+impl GravityAxis {
+	pub const ALL: [GravityAxis; 6] = [
+		GravityAxis::PosX,
+		GravityAxis::NegX,
+		GravityAxis::PosY,
+		GravityAxis::NegY,
+		GravityAxis::PosZ,
+		GravityAxis::NegZ,
+	];
+
+	pub fn expected_g(self) -> [f32; 3] {
+		match self {
+			GravityAxis::PosX => [1.0, 0.0, 0.0],
+			GravityAxis::NegX => [-1.0, 0.0, 0.0],
+			GravityAxis::PosY => [0.0, 1.0, 0.0],
+			GravityAxis::NegY => [0.0, -1.0, 0.0],
+			GravityAxis::PosZ => [0.0, 0.0, 1.0],
+			GravityAxis::NegZ => [0.0, 0.0, -1.0],
+		}
+	}
+}
+
+// In the sensor frame, like OFFSET_USER. Data read through a driver with a `Mounting` is in the body frame,
+// so correct it with `mounted(mounting)`.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Calibration {
+	pub gyro_bias_dps: [f32; 3],
+	pub accel_offset_g: [f32; 3],
+}
+
+impl Calibration {
+	// The same correction on body axes. Only for `apply_*`, OFFSET_USER needs the sensor frame one.
+	pub fn mounted(&self, mounting: &Mounting) -> Calibration {
+		Calibration {
+			gyro_bias_dps: mounting.apply(self.gyro_bias_dps),
+			accel_offset_g: mounting.apply(self.accel_offset_g),
+		}
+	}
+
+	pub fn apply_gyro(&self, data: &GyroData) -> [f32; 3] {
+		let dps = data.dps();
+		core::array::from_fn(|i| dps[i] - self.gyro_bias_dps[i])
+	}
+
+	pub fn apply_accel(&self, data: &AccelData) -> [f32; 3] {
+		let g = data.g();
+		core::array::from_fn(|i| g[i] - self.accel_offset_g[i])
+	}
+
+	// Correction in OFFSET_USER units (1/32 dps, 1/2 mg). The device adds these to its output.
+	pub fn offset_user_lsb(&self) -> ([i16; 3], [i16; 3]) {
+		(
+			self.gyro_bias_dps.map(|v| round(-v * 32.0)),
+			self.accel_offset_g.map(|v| round(-v * 2000.0)),
+		)
+	}
+}
+
+// Running mean and variance (Welford) of accel and gyro samples taken at rest.
+pub struct RestAccumulator {
+	count: u32,
+	accel_mean: [f32; 3],
+	accel_m2: [f32; 3],
+	gyro_mean: [f32; 3],
+	gyro_m2: [f32; 3],
+}
+
+impl RestAccumulator {
+	pub fn new() -> Self {
+		Self {
+			count: 0,
+			accel_mean: [0.0; 3],
+			accel_m2: [0.0; 3],
+			gyro_mean: [0.0; 3],
+			gyro_m2: [0.0; 3],
+		}
+	}
+
+	pub fn push(&mut self, accel_g: [f32; 3], gyro_dps: [f32; 3]) {
+		self.count += 1;
+		let n = self.count as f32;
+		for i in 0..3 {
+			let d = accel_g[i] - self.accel_mean[i];
+			self.accel_mean[i] += d / n;
+			self.accel_m2[i] += d * (accel_g[i] - self.accel_mean[i]);
+
+			let d = gyro_dps[i] - self.gyro_mean[i];
+			self.gyro_mean[i] += d / n;
+			self.gyro_m2[i] += d * (gyro_dps[i] - self.gyro_mean[i]);
+		}
+	}
+
+	pub fn count(&self) -> u32 {
+		self.count
+	}
+
+	pub fn is_at_rest(&self) -> bool {
+		if self.count < 2 {
+			return false;
+		}
+		let n = (self.count - 1) as f32;
+		let accel_limit = MAX_REST_ACCEL_STD_G * MAX_REST_ACCEL_STD_G;
+		let gyro_limit = MAX_REST_GYRO_STD_DPS * MAX_REST_GYRO_STD_DPS;
+		self.accel_m2.iter().all(|m2| m2 / n <= accel_limit)
+			&& self.gyro_m2.iter().all(|m2| m2 / n <= gyro_limit)
+	}
+
+	pub fn accel_mean_g(&self) -> [f32; 3] {
+		self.accel_mean
+	}
+
+	pub fn gyro_mean_dps(&self) -> [f32; 3] {
+		self.gyro_mean
+	}
+
+	// None if the device moved during the run.
+	pub fn finish(&self, gravity: GravityAxis) -> Option<Calibration> {
+		if !self.is_at_rest() {
+			return None;
+		}
+		let expected = gravity.expected_g();
+		Some(Calibration {
+			gyro_bias_dps: self.gyro_mean,
+			accel_offset_g: core::array::from_fn(|i| self.accel_mean[i] - expected[i]),
+		})
+	}
+}
+
+impl Default for RestAccumulator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// f32::round is not available in core.
+pub(crate) fn round(v: f32) -> i16 {
+	let r = if v < 0.0 { v - 0.5 } else { v + 0.5 };
+	r.clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::data::AxisMask;
+	use crate::mounting::{AxisRotation, SignedAxis};
+	use crate::register::bank0::{AccelFs, GyroFs};
+
+	fn mounting() -> Mounting {
+		AxisRotation::new(SignedAxis::NegY, SignedAxis::PosZ).unwrap().into()
+	}
+
+	fn assert_close(a: [f32; 3], b: [f32; 3]) {
+		for i in 0..3 {
+			assert!((a[i] - b[i]).abs() < 1e-5, "{a:?} != {b:?}");
+		}
+	}
+
+	#[test]
+	fn rest_accumulator_mean_and_rest_check() {
+		let mut accumulator = RestAccumulator::new();
+		assert!(!accumulator.is_at_rest());
+		assert_eq!(accumulator.finish(GravityAxis::PosZ), None);
+		for i in 0..100 {
+			// ±0.005 g and ±0.25 dps of noise, half the rest limits
+			let noise = if i % 2 == 0 { 0.5 } else { -0.5 };
+			accumulator.push([0.02 + 0.01 * noise, -0.01, 1.03], [1.5 + 0.5 * noise, -0.5, 0.25]);
+		}
+		assert_eq!(accumulator.count(), 100);
+		assert_close(accumulator.accel_mean_g(), [0.02, -0.01, 1.03]);
+		assert_close(accumulator.gyro_mean_dps(), [1.5, -0.5, 0.25]);
+
+		let calibration = accumulator.finish(GravityAxis::PosZ).unwrap();
+		assert_close(calibration.accel_offset_g, [0.02, -0.01, 0.03]);
+		assert_close(calibration.gyro_bias_dps, [1.5, -0.5, 0.25]);
+		assert_eq!(calibration.offset_user_lsb(), ([-48, 16, -8], [-40, 20, -60]));
+	}
+
+	#[test]
+	fn rest_accumulator_rejects_motion() {
+		let mut accumulator = RestAccumulator::new();
+		for i in 0..100 {
+			let swing = if i % 2 == 0 { 0.05 } else { -0.05 };
+			accumulator.push([0.0, 0.0, 1.0 + swing], [0.0; 3]);
+		}
+		assert!(!accumulator.is_at_rest());
+		assert_eq!(accumulator.finish(GravityAxis::PosZ), None);
+	}
+
+	#[test]
+	fn mounted_calibration_matches_body_frame_data() {
+		let calibration = Calibration { gyro_bias_dps: [0.5, -1.0, 2.0], accel_offset_g: [0.01, 0.02, -0.03] };
+		let mounting = mounting();
+		let gyro = GyroData { raw: [100, -200, 300], fs: GyroFs::Dps250, axes: AxisMask::ALL };
+		let accel = AccelData { raw: [1000, -2000, 16000], fs: AccelFs::G2, axes: AxisMask::ALL };
+		let body_gyro = GyroData { raw: mounting.apply_i16(gyro.raw), ..gyro };
+		let body_accel = AccelData { raw: mounting.apply_i16(accel.raw), ..accel };
+
+		let body = calibration.mounted(&mounting);
+		assert_close(body.apply_gyro(&body_gyro), mounting.apply(calibration.apply_gyro(&gyro)));
+		assert_close(body.apply_accel(&body_accel), mounting.apply(calibration.apply_accel(&accel)));
+	}
+
+	#[test]
+	fn mounted_six_position_and_thermal_models_match_body_frame_data() {
+		let mounting = mounting();
+		let sensor = [0.3, -0.2, 1.1];
+		let accel = AccelCalibration { bias_g: [0.01, -0.02, 0.03], matrix: [[1.02, 0.01, 0.0], [0.0, 0.99, -0.02], [0.03, 0.0, 1.01]] };
+		assert_close(accel.mounted(&mounting).apply(mounting.apply(sensor)), mounting.apply(accel.apply(sensor)));
+
+		let thermal = GyroThermalModel { coefficients: [[0.1, 0.2], [-0.3, 0.05], [1.0, -0.4]] };
+		assert_close(thermal.mounted(&mounting).bias_dps(40.0), mounting.apply(thermal.bias_dps(40.0)));
+	}
+}
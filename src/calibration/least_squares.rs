@@ -0,0 +1,68 @@
+// Linear least squares through the normal equations, sized at compile time so no allocation is needed.
+pub struct LeastSquares<const N: usize> {
+	ata: [[f32; N]; N],
+	atb: [f32; N],
+	rows: u32,
+}
+
+impl<const N: usize> LeastSquares<N> {
+	pub fn new() -> Self {
+		Self { ata: [[0.0; N]; N], atb: [0.0; N], rows: 0 }
+	}
+
+	// Adds the observation `x · solution = y`.
+	pub fn push(&mut self, x: [f32; N], y: f32) {
+		for i in 0..N {
+			for j in 0..N {
+				self.ata[i][j] += x[i] * x[j];
+			}
+			self.atb[i] += x[i] * y;
+		}
+		self.rows += 1;
+	}
+
+	pub fn rows(&self) -> u32 {
+		self.rows
+	}
+
+	// None if the observations do not determine every unknown.
+	pub fn solve(&self) -> Option<[f32; N]> {
+		solve(self.ata, self.atb)
+	}
+}
+
+impl<const N: usize> Default for LeastSquares<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+// Gaussian elimination with partial pivoting. None if `a` is singular.
+// A pivot counts as zero when it is within rounding error of the largest entry, since the normal
+// equation sums of a singular system rarely cancel to exactly zero in f32.
+pub fn solve<const N: usize>(mut a: [[f32; N]; N], mut b: [f32; N]) -> Option<[f32; N]> {
+	let scale = a.iter().flatten().fold(0.0, |max, v| f32::max(max, v.abs()));
+	let tolerance = scale * N as f32 * 8.0 * f32::EPSILON;
+	for col in 0..N {
+		let pivot = (col..N).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+		if a[pivot][col].abs() <= tolerance {
+			return None;
+		}
+		a.swap(col, pivot);
+		b.swap(col, pivot);
+		for row in col + 1..N {
+			let factor = a[row][col] / a[col][col];
+			for k in col..N {
+				a[row][k] -= factor * a[col][k];
+			}
+			b[row] -= factor * b[col];
+		}
+	}
+
+	let mut x = [0.0; N];
+	for row in (0..N).rev() {
+		let sum: f32 = (row + 1..N).map(|k| a[row][k] * x[k]).sum();
+		x[row] = (b[row] - sum) / a[row][row];
+	}
+	Some(x)
+}
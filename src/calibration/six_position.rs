@@ -0,0 +1,178 @@
+use crate::data::AccelData;
+use crate::mounting::Mounting;
+
+use super::least_squares::{solve, LeastSquares};
+use super::GravityAxis;
+
+// Accelerometer model: `corrected = matrix * (measured - bias)`.
+// The diagonal of `matrix` holds the per-axis scale factors and the off-diagonal terms the cross-axis misalignment.
+// Fitted on the sensor axes; `mounted` gives the model for body frame data.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AccelCalibration {
+	pub bias_g: [f32; 3],
+	pub matrix: [[f32; 3]; 3],
+}
+
+impl AccelCalibration {
+	pub const IDENTITY: AccelCalibration = AccelCalibration {
+		bias_g: [0.0; 3],
+		matrix: [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+	};
+	pub const SERIALIZED_LEN: usize = 48;
+
+	pub fn apply(&self, g: [f32; 3]) -> [f32; 3] {
+		let d: [f32; 3] = core::array::from_fn(|i| g[i] - self.bias_g[i]);
+		self.matrix.map(|row| row[0] * d[0] + row[1] * d[1] + row[2] * d[2])
+	}
+
+	pub fn mounted(&self, mounting: &Mounting) -> AccelCalibration {
+		AccelCalibration { bias_g: mounting.apply(self.bias_g), matrix: mounting.apply_to_map(self.matrix) }
+	}
+
+	pub fn scale(&self) -> [f32; 3] {
+		core::array::from_fn(|i| self.matrix[i][i])
+	}
+
+	// `matrix` with unit diagonal.
+	pub fn misalignment(&self) -> [[f32; 3]; 3] {
+		core::array::from_fn(|i| self.matrix[i].map(|v| v / self.matrix[i][i]))
+	}
+
+	// Little endian f32s: bias, then the matrix row by row.
+	pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_LEN] {
+		let mut bytes = [0u8; Self::SERIALIZED_LEN];
+		let values = self.bias_g.iter().chain(self.matrix.iter().flatten());
+		for (chunk, v) in bytes.as_chunks_mut::<4>().0.iter_mut().zip(values) {
+			*chunk = v.to_le_bytes();
+		}
+		bytes
+	}
+
+	pub fn from_bytes(bytes: &[u8; Self::SERIALIZED_LEN]) -> Self {
+		let chunks = bytes.as_chunks::<4>().0;
+		let v = |i: usize| f32::from_le_bytes(chunks[i]);
+		AccelCalibration {
+			bias_g: [v(0), v(1), v(2)],
+			matrix: core::array::from_fn(|row| core::array::from_fn(|col| v(3 + 3 * row + col))),
+		}
+	}
+}
+
+impl Default for AccelCalibration {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl AccelData {
+	// `calibration` must be in the same frame as the data, see `AccelCalibration::mounted`.
+	pub fn calibrated_g(&self, calibration: &AccelCalibration) -> [f32; 3] {
+		calibration.apply(self.g())
+	}
+}
+
+// Collects one averaged reading per face and solves for `AccelCalibration`.
+pub struct SixPositionCalibrator {
+	faces: [Option<[f32; 3]>; 6],
+}
+
+impl SixPositionCalibrator {
+	pub fn new() -> Self {
+		Self { faces: [None; 6] }
+	}
+
+	// `mean_g` is the averaged sensor frame reading with the sensor's `up` axis pointing away from the earth.
+	// A face can be retaken.
+	pub fn add(&mut self, up: GravityAxis, mean_g: [f32; 3]) {
+		self.faces[up as usize] = Some(mean_g);
+	}
+
+	pub fn is_collected(&self, up: GravityAxis) -> bool {
+		self.faces[up as usize].is_some()
+	}
+
+	// Next face that still needs a reading.
+	pub fn next_face(&self) -> Option<GravityAxis> {
+		GravityAxis::ALL.into_iter().find(|&face| !self.is_collected(face))
+	}
+
+	// None until all six faces are collected, or if the readings are degenerate.
+	pub fn solve(&self) -> Option<AccelCalibration> {
+		// Each row of the affine map `corrected = a * measured + c` is fitted independently.
+		let mut fits = [LeastSquares::<4>::new(), LeastSquares::new(), LeastSquares::new()];
+		for face in GravityAxis::ALL {
+			let measured = self.faces[face as usize]?;
+			let expected = face.expected_g();
+			for (fit, expected) in fits.iter_mut().zip(expected) {
+				fit.push([measured[0], measured[1], measured[2], 1.0], expected);
+			}
+		}
+
+		let mut a = [[0.0; 3]; 3];
+		let mut c = [0.0; 3];
+		for (i, fit) in fits.iter().enumerate() {
+			let row = fit.solve()?;
+			a[i] = [row[0], row[1], row[2]];
+			c[i] = row[3];
+		}
+		// a * measured + c = a * (measured - bias)  =>  a * bias = -c
+		let bias_g = solve(a, c.map(|v| -v))?;
+		Some(AccelCalibration { bias_g, matrix: a })
+	}
+}
+
+impl Default for SixPositionCalibrator {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Reading of a face that `calibration` corrects to exactly 1 g along `up`.
+	fn measured(calibration: &AccelCalibration, up: GravityAxis) -> [f32; 3] {
+		let d = solve(calibration.matrix, up.expected_g()).unwrap();
+		core::array::from_fn(|i| d[i] + calibration.bias_g[i])
+	}
+
+	#[test]
+	fn solve_recovers_bias_and_matrix() {
+		let known = AccelCalibration {
+			bias_g: [0.02, -0.015, 0.04],
+			matrix: [[1.03, 0.012, -0.008], [-0.005, 0.98, 0.015], [0.01, -0.02, 1.01]],
+		};
+		let mut calibrator = SixPositionCalibrator::new();
+		for up in GravityAxis::ALL {
+			assert_eq!(calibrator.solve(), None);
+			assert_eq!(calibrator.next_face(), Some(up));
+			calibrator.add(up, measured(&known, up));
+		}
+		assert_eq!(calibrator.next_face(), None);
+
+		let solved = calibrator.solve().unwrap();
+		for i in 0..3 {
+			assert!((solved.bias_g[i] - known.bias_g[i]).abs() < 1e-4, "{solved:?}");
+			for j in 0..3 {
+				assert!((solved.matrix[i][j] - known.matrix[i][j]).abs() < 1e-4, "{solved:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn identical_faces_are_degenerate() {
+		let mut calibrator = SixPositionCalibrator::new();
+		for up in GravityAxis::ALL {
+			calibrator.add(up, [0.0, 0.0, 1.0]);
+		}
+		assert_eq!(calibrator.solve(), None);
+	}
+
+	#[test]
+	fn bytes_round_trip() {
+		let calibration = AccelCalibration { bias_g: [0.1, -0.2, 0.3], matrix: [[1.0, 0.01, 0.02], [0.03, 0.99, 0.04], [0.05, 0.06, 1.01]] };
+		assert_eq!(AccelCalibration::from_bytes(&calibration.to_bytes()), calibration);
+	}
+}
@@ -0,0 +1,190 @@
+use crate::data::{AxisMask, GyroData, Temperature};
+use crate::mounting::Mounting;
+
+use super::least_squares::LeastSquares;
+
+// Polynomials are evaluated in `(t - 25 °C) / 10` to keep the normal equations well conditioned in f32.
+const REFERENCE_C: f32 = 25.0;
+const SCALE_C: f32 = 10.0;
+// Temperatures closer than this count as one point when checking that the fit is determined.
+const MIN_DISTINCT_C: f32 = 0.5;
+
+fn powers<const N: usize>(temperature_c: f32) -> [f32; N] {
+	let x = (temperature_c - REFERENCE_C) / SCALE_C;
+	let mut p = [1.0; N];
+	for k in 1..N {
+		p[k] = p[k - 1] * x;
+	}
+	p
+}
+
+// Per-axis gyro bias as a polynomial of die temperature with `N` coefficients (N = 2 is linear, 3 quadratic, ...).
+// The axes are the sensor's; `mounted` gives the model for body frame data.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GyroThermalModel<const N: usize> {
+	pub coefficients: [[f32; N]; 3],
+}
+
+impl<const N: usize> GyroThermalModel<N> {
+	pub fn bias_dps(&self, temperature_c: f32) -> [f32; 3] {
+		let p = powers::<N>(temperature_c);
+		self.coefficients.map(|c| c.iter().zip(p).map(|(c, p)| c * p).sum())
+	}
+
+	pub fn mounted(&self, mounting: &Mounting) -> GyroThermalModel<N> {
+		let mut coefficients = self.coefficients;
+		for k in 0..N {
+			let rotated = mounting.apply([self.coefficients[0][k], self.coefficients[1][k], self.coefficients[2][k]]);
+			for (axis, v) in coefficients.iter_mut().zip(rotated) {
+				axis[k] = v;
+			}
+		}
+		GyroThermalModel { coefficients }
+	}
+
+	// `temperature` must come from the same burst as `gyro`, and `gyro` be in the model's frame.
+	pub fn apply(&self, gyro: &GyroData, temperature: &Temperature) -> [f32; 3] {
+		let dps = gyro.dps();
+		let bias = self.bias_dps(temperature.celsius());
+		core::array::from_fn(|i| dps[i] - bias[i])
+	}
+}
+
+// Records (temperature, bias) pairs, e.g. during a warm-up run at rest, and fits `GyroThermalModel`.
+// Each axis is fitted on its own, so an axis that was disabled for part of the run only uses the points it measured.
+pub struct GyroThermalFit<const N: usize> {
+	fits: [LeastSquares<N>; 3],
+	min_c: f32,
+	max_c: f32,
+	distinct_c: [[f32; N]; 3],
+	distinct: [usize; 3],
+}
+
+impl<const N: usize> GyroThermalFit<N> {
+	pub fn new() -> Self {
+		Self {
+			fits: [LeastSquares::new(), LeastSquares::new(), LeastSquares::new()],
+			min_c: f32::MAX,
+			max_c: f32::MIN,
+			distinct_c: [[0.0; N]; 3],
+			distinct: [0; 3],
+		}
+	}
+
+	// NaN biases, from disabled axes, are skipped for that axis only.
+	pub fn push(&mut self, temperature_c: f32, bias_dps: [f32; 3]) {
+		let p = powers::<N>(temperature_c);
+		for axis in 0..3 {
+			if bias_dps[axis].is_nan() {
+				continue;
+			}
+			self.fits[axis].push(p, bias_dps[axis]);
+			// N distinct temperatures are all the solve needs to know about
+			let (distinct_c, distinct) = (&mut self.distinct_c[axis], &mut self.distinct[axis]);
+			let known = distinct_c[..*distinct].iter().any(|t| (t - temperature_c).abs() < MIN_DISTINCT_C);
+			if !known && *distinct < N {
+				distinct_c[*distinct] = temperature_c;
+				*distinct += 1;
+			}
+		}
+		self.min_c = self.min_c.min(temperature_c);
+		self.max_c = self.max_c.max(temperature_c);
+	}
+
+	// Points recorded on the best measured axis.
+	pub fn points(&self) -> u32 {
+		self.fits.iter().map(|fit| fit.rows()).max().unwrap_or(0)
+	}
+
+	// Axes with at least one measured point.
+	pub fn measured_axes(&self) -> AxisMask {
+		let [x, y, z] = self.fits.each_ref().map(|fit| fit.rows() > 0);
+		AxisMask { x, y, z }
+	}
+
+	// Temperature span covered so far. The model should not be trusted far outside it.
+	pub fn range_c(&self) -> Option<(f32, f32)> {
+		(self.points() > 0).then_some((self.min_c, self.max_c))
+	}
+
+	// Axes that were never measured get a zero bias model, see `measured_axes`. None if nothing was measured,
+	// or a measured axis has fewer temperatures at least 0.5 °C apart than coefficients or is singular.
+	pub fn solve(&self) -> Option<GyroThermalModel<N>> {
+		if self.points() == 0 {
+			return None;
+		}
+		let mut coefficients = [[0.0; N]; 3];
+		for axis in 0..3 {
+			if self.fits[axis].rows() == 0 {
+				continue;
+			}
+			if self.distinct[axis] < N {
+				return None;
+			}
+			coefficients[axis] = self.fits[axis].solve()?;
+		}
+		Some(GyroThermalModel { coefficients })
+	}
+}
+
+impl<const N: usize> Default for GyroThermalFit<N> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MODEL: GyroThermalModel<3> = GyroThermalModel { coefficients: [[0.5, -0.2, 0.03], [-1.0, 0.4, -0.05], [0.2, 0.1, 0.01]] };
+
+	fn assert_coefficients(solved: [f32; 3], expected: [f32; 3]) {
+		for k in 0..3 {
+			assert!((solved[k] - expected[k]).abs() < 1e-4, "{solved:?} != {expected:?}");
+		}
+	}
+
+	#[test]
+	fn solve_recovers_a_polynomial() {
+		let mut fit = GyroThermalFit::<3>::new();
+		for i in 0..20 {
+			let t = 20.0 + i as f32 * 1.5;
+			assert_eq!(fit.solve().is_some(), i >= 3);
+			fit.push(t, MODEL.bias_dps(t));
+		}
+		assert_eq!(fit.points(), 20);
+		assert_eq!(fit.range_c(), Some((20.0, 48.5)));
+		let solved = fit.solve().unwrap();
+		for axis in 0..3 {
+			assert_coefficients(solved.coefficients[axis], MODEL.coefficients[axis]);
+		}
+	}
+
+	#[test]
+	fn disabled_axis_does_not_block_the_others() {
+		let mut fit = GyroThermalFit::<3>::new();
+		for i in 0..20 {
+			let t = 20.0 + i as f32 * 1.5;
+			let [_, y, z] = MODEL.bias_dps(t);
+			fit.push(t, [f32::NAN, y, z]);
+		}
+		assert_eq!(fit.points(), 20);
+		assert_eq!(fit.measured_axes(), AxisMask { x: false, y: true, z: true });
+		let solved = fit.solve().unwrap();
+		assert_eq!(solved.coefficients[0], [0.0; 3]);
+		assert_coefficients(solved.coefficients[1], MODEL.coefficients[1]);
+		assert_coefficients(solved.coefficients[2], MODEL.coefficients[2]);
+	}
+
+	#[test]
+	fn too_few_distinct_temperatures() {
+		let mut fit = GyroThermalFit::<3>::new();
+		for t in [30.0, 30.1, 30.2, 40.0, 40.3] {
+			fit.push(t, MODEL.bias_dps(t));
+		}
+		assert_eq!(fit.solve(), None);
+		assert_eq!(GyroThermalFit::<3>::new().solve(), None);
+	}
+}
@@ -0,0 +1,50 @@
+use crate::register::bank0::Odr;
+
+// ODRs are divided down from a 32 kHz base clock, which is CLKIN on pin 9 in RTC mode.
+pub const NOMINAL_BASE_CLOCK_HZ: u32 = 32_000;
+// CLKIN frequencies the datasheet allows in RTC mode.
+pub const CLKIN_RANGE_HZ: core::ops::RangeInclusive<u32> = 31_000..=50_000;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockInput {
+	#[default]
+	Internal,
+	Clkin(u32),  // RTC mode with the given CLKIN frequency in Hz, see `CLKIN_RANGE_HZ`
+}
+
+impl ClockInput {
+	// Never 0, so a hand built `Clkin(0)` can't divide by zero.
+	pub fn base_clock_hz(self) -> u32 {
+		match self {
+			ClockInput::Internal => NOMINAL_BASE_CLOCK_HZ,
+			ClockInput::Clkin(hz) => hz.max(1),
+		}
+	}
+
+	// Real sample period of `odr`, e.g. 1 kHz becomes 1.024 kHz with a 32.768 kHz crystal.
+	pub fn period_ns(self, odr: Odr) -> u64 {
+		let nominal_ns = (odr.period_us() * 1000.0) as u64;
+		nominal_ns * NOMINAL_BASE_CLOCK_HZ as u64 / self.base_clock_hz() as u64
+	}
+
+	pub fn odr_hz(self, odr: Odr) -> f32 {
+		match self.period_ns(odr) {
+			0 => 0.0,
+			period_ns => 1e9 / period_ns as f32,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clkin_scales_the_odr() {
+		assert_eq!(ClockInput::Internal.period_ns(Odr::Hz1k), 1_000_000);
+		assert_eq!(ClockInput::Clkin(32_768).period_ns(Odr::Hz1k), 976_562);
+		assert_eq!(ClockInput::Clkin(0).period_ns(Odr::Hz1k), 32_000_000_000);
+		assert_eq!(ClockInput::Internal.odr_hz(Odr::Reserved0), 0.0);
+	}
+}
@@ -0,0 +1,236 @@
+use crate::register::bank0::{AccelFs, GyroFs};
+
+// Enabled axes of a sensor (SENSOR_CONFIG0). Disabled axes keep a stale `raw` value, the float accessors give NaN.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AxisMask {
+	pub x: bool,
+	pub y: bool,
+	pub z: bool,
+}
+
+impl AxisMask {
+	pub const ALL: AxisMask = AxisMask { x: true, y: true, z: true };
+	pub const NONE: AxisMask = AxisMask { x: false, y: false, z: false };
+
+	pub fn to_array(self) -> [bool; 3] {
+		[self.x, self.y, self.z]
+	}
+
+	// `None` for the values of disabled axes.
+	pub fn apply<V: Copy>(self, values: [V; 3]) -> [Option<V>; 3] {
+		let enabled = self.to_array();
+		core::array::from_fn(|i| enabled[i].then_some(values[i]))
+	}
+
+	// NaN for the values of disabled axes, so stale readings can't pass for measurements.
+	pub fn nan_disabled(self, values: [f32; 3]) -> [f32; 3] {
+		self.apply(values).map(|v| v.unwrap_or(f32::NAN))
+	}
+}
+
+impl Default for AxisMask {
+	fn default() -> Self {
+		Self::ALL
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AccelData {
+	pub raw: [i16; 3],
+	pub fs: AccelFs,
+	pub axes: AxisMask,
+}
+
+impl AccelData {
+	// NaN for disabled axes, whose `raw` value is stale.
+	pub fn g(&self) -> [f32; 3] {
+		let lsb_per_g = self.fs.lsb_per_g();
+		self.axes.nan_disabled(self.raw.map(|v| v as f32 / lsb_per_g))
+	}
+
+	pub fn g_axes(&self) -> [Option<f32>; 3] {
+		self.axes.apply(self.g())
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GyroData {
+	pub raw: [i16; 3],
+	pub fs: GyroFs,
+	pub axes: AxisMask,
+}
+
+impl GyroData {
+	// NaN for disabled axes, whose `raw` value is stale.
+	pub fn dps(&self) -> [f32; 3] {
+		let lsb_per_dps = self.fs.lsb_per_dps();
+		self.axes.nan_disabled(self.raw.map(|v| v as f32 / lsb_per_dps))
+	}
+
+	pub fn dps_axes(&self) -> [Option<f32>; 3] {
+		self.axes.apply(self.dps())
+	}
+}
+
+// Temperature, accel and gyro of the same sample.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ImuSample {
+	pub temperature: Temperature,
+	pub accel: AccelData,
+	pub gyro: GyroData,
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Temperature {
+	pub raw: i16,
+}
+
+impl Temperature {
+	pub fn celsius(&self) -> f32 {
+		self.raw as f32 / 132.48 + 25.0
+	}
+}
+
+// Integer conversions for targets without an FPU, rounded half away from zero like `calibration::round`.
+#[cfg(feature = "fixed-point")]
+mod fixed_point {
+	use super::{AccelData, GyroData, Temperature};
+	use crate::register::bank0::{AccelFs, GyroFs};
+
+	// lsb_per_g = (32768 << fs) / 16 and lsb_per_dps = (32768 << fs) / 2000,
+	// times 16 (`hires_shift` 4) for 20 bit FIFO data
+	pub(crate) fn milli_g(raw: i32, fs: AccelFs, hires_shift: u8) -> i32 {
+		div_round(raw as i64 * 1000 * 16, 32768 << (fs as u8 + hires_shift))
+	}
+
+	pub(crate) fn milli_dps(raw: i32, fs: GyroFs, hires_shift: u8) -> i32 {
+		div_round(raw as i64 * 1000 * 2000, 32768 << (fs as u8 + hires_shift))
+	}
+
+	// 132.48 LSB/°C from the data registers, 2.07 LSB/°C from 8 bit FIFO temperature
+	// the 25 °C offset goes in before rounding, so the result rounds like `celsius() * 100`
+	pub(crate) fn centi_celsius(raw: i16, lsb_per_c_x100: i64) -> i32 {
+		div_round(raw as i64 * 100 * 100 + 2500 * lsb_per_c_x100, lsb_per_c_x100)
+	}
+
+	fn div_round(num: i64, den: i64) -> i32 {
+		let half = den / 2;
+		let q = if num < 0 { (num - half) / den } else { (num + half) / den };
+		q as i32
+	}
+
+	// Integers have no NaN, disabled axes give the stale value here and have to be checked against `axes`.
+	impl AccelData {
+		pub fn milli_g(&self) -> [i32; 3] {
+			self.raw.map(|v| milli_g(v as i32, self.fs, 0))
+		}
+	}
+
+	impl GyroData {
+		pub fn milli_dps(&self) -> [i32; 3] {
+			self.raw.map(|v| milli_dps(v as i32, self.fs, 0))
+		}
+	}
+
+	impl Temperature {
+		pub fn centi_celsius(&self) -> i32 {
+			centi_celsius(self.raw, 13248)
+		}
+	}
+}
+
+#[cfg(feature = "fixed-point")]
+pub(crate) use fixed_point::{centi_celsius, milli_dps, milli_g};
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::register::bank0::AccelFs;
+
+	#[test]
+	fn disabled_axes_are_masked() {
+		let mask = AxisMask { x: true, y: false, z: true };
+		assert_eq!(mask.apply([1, 2, 3]), [Some(1), None, Some(3)]);
+		assert_eq!(AxisMask::NONE.apply([1, 2, 3]), [None; 3]);
+		let values = mask.nan_disabled([1.0, 2.0, 3.0]);
+		assert_eq!((values[0], values[2]), (1.0, 3.0));
+		assert!(values[1].is_nan());
+		assert_eq!(AxisMask::default().to_array(), [true; 3]);
+	}
+
+	#[test]
+	fn stale_raw_values_read_as_nan() {
+		let accel = AccelData { raw: [2048, 4096, -2048], fs: AccelFs::G16, axes: AxisMask { x: false, y: true, z: true } };
+		let g = accel.g();
+		assert!(g[0].is_nan());
+		assert_eq!((g[1], g[2]), (2.0, -1.0));
+		assert_eq!(accel.g_axes(), [None, Some(2.0), Some(-1.0)]);
+	}
+
+	#[cfg(feature = "fixed-point")]
+	mod fixed_point {
+		use super::super::*;
+		use crate::register::bank0::{AccelFs, GyroFs};
+
+		const ACCEL_FS: [AccelFs; 4] = [AccelFs::G16, AccelFs::G8, AccelFs::G4, AccelFs::G2];
+
+		// Half away from zero. The references below are exact in f64, so half-way values stay half-way.
+		fn round(v: f64) -> i32 {
+			(if v < 0.0 { v - 0.5 } else { v + 0.5 }) as i32
+		}
+
+		fn gyro_fs() -> impl Iterator<Item = GyroFs> {
+			(0..8).map(|n| GyroFs::n(n).unwrap())
+		}
+
+		#[test]
+		fn milli_g_matches_float_path() {
+			// `g()` divides by a power of two, which is exact in f32
+			for fs in ACCEL_FS {
+				for raw in i16::MIN..=i16::MAX {
+					let data = AccelData { raw: [raw, 0, 0], fs, axes: AxisMask::ALL };
+					assert_eq!(data.milli_g()[0], round(data.g()[0] as f64 * 1000.0), "{fs:?} {raw}");
+				}
+			}
+		}
+
+		#[test]
+		fn milli_dps_matches_float_path() {
+			// `dps()` divides by 16.384, which f32 can't hold, so the exact reference is the same formula in f64
+			for fs in gyro_fs() {
+				for raw in i16::MIN..=i16::MAX {
+					let data = GyroData { raw: [raw, 0, 0], fs, axes: AxisMask::ALL };
+					let milli_dps = data.milli_dps()[0];
+					assert_eq!(milli_dps, round(raw as f64 * 2_000_000.0 / (32768u64 << fs as u8) as f64), "{fs:?} {raw}");
+					assert!((milli_dps - round(data.dps()[0] as f64 * 1000.0)).abs() <= 1, "{fs:?} {raw}");
+				}
+			}
+		}
+
+		#[test]
+		fn centi_celsius_matches_float_path() {
+			for raw in i16::MIN..=i16::MAX {
+				let temperature = Temperature { raw };
+				let centi_celsius = temperature.centi_celsius();
+				assert_eq!(centi_celsius, round(raw as f64 * 10000.0 / 13248.0 + 2500.0), "{raw}");
+				assert!((centi_celsius - round(temperature.celsius() as f64 * 100.0)).abs() <= 1, "{raw}");
+			}
+		}
+
+		#[test]
+		fn half_way_values_round_away_from_zero() {
+			let accel = AccelData { raw: [1024, -1024, i16::MIN], fs: AccelFs::G2, axes: AxisMask::ALL };
+			assert_eq!(accel.milli_g(), [63, -63, -2000]);
+			let gyro = GyroData { raw: [128, -128, i16::MIN], fs: GyroFs::Dps2000, axes: AxisMask::ALL };
+			assert_eq!(gyro.milli_dps(), [7813, -7813, -2_000_000]);
+			// 2812.5 and -22187.5 hundredths
+			assert_eq!(Temperature { raw: 414 }.centi_celsius(), 2813);
+			assert_eq!(Temperature { raw: -32706 }.centi_celsius(), -22188);
+		}
+	}
+}
@@ -0,0 +1,12 @@
+use crate::register::bank0::Odr;
+
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error<E> {
+	Transport(E),
+	WhoAmI(u8),  // unexpected WHO_AM_I value
+	Motion,  // the device moved while it was expected to be at rest
+	Mounting,  // the mounting has no APEX MOUNTING_MATRIX equivalent
+	ClkinFrequency(u32),  // outside `clock::CLKIN_RANGE_HZ`
+	LowPowerOdr(Odr),  // the accel low power mode can't run at this ODR
+}
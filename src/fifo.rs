@@ -0,0 +1,618 @@
+use crate::clock::ClockInput;
+use crate::data::{AccelData, AxisMask, GyroData};
+use crate::mounting::Mounting;
+use crate::register::bank0::{AccelFs, Endian, FifoMode, GyroFs, Odr};
+use crate::timestamp::{TickPeriod, Timestamp, TimestampConfig};
+
+// FIFO packet header bits.
+const HEADER_MSG: u8 = 1 << 7;  // FIFO is empty
+const HEADER_ACCEL: u8 = 1 << 6;
+const HEADER_GYRO: u8 = 1 << 5;
+const HEADER_20: u8 = 1 << 4;
+const HEADER_TIMESTAMP_FSYNC: u8 = 0b11 << 2;
+const HEADER_ODR_TIMESTAMP: u8 = 0b10 << 2;
+const HEADER_FSYNC_TIMESTAMP: u8 = 0b11 << 2;
+const HEADER_ODR_ACCEL: u8 = 1 << 1;
+const HEADER_ODR_GYRO: u8 = 1;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FifoSettings {
+	pub mode: FifoMode,
+	pub accel: bool,
+	pub gyro: bool,
+	pub temperature: bool,
+	pub timestamp_fsync: bool,  // timestamps in 16 byte packets
+	pub hires: bool,  // 20 byte packets with 20 bit data
+	pub watermark: u16,  // bytes, or records with FIFO_COUNT_REC
+}
+
+impl FifoSettings {
+	// power-on reset value: bypass mode, nothing written to the FIFO
+	pub const RESET: FifoSettings = FifoSettings {
+		mode: FifoMode::Bypass,
+		accel: false,
+		gyro: false,
+		temperature: false,
+		timestamp_fsync: false,
+		hires: false,
+		watermark: 0,
+	};
+
+	// Size of the packets this configuration produces, 0 if nothing is written to the FIFO.
+	pub fn packet_len(&self) -> usize {
+		match (self.accel, self.gyro) {
+			_ if self.hires => 20,
+			(true, true) => 16,
+			(true, false) | (false, true) => 8,
+			(false, false) => 0,
+		}
+	}
+}
+
+impl Default for FifoSettings {
+	fn default() -> Self {
+		Self {
+			mode: FifoMode::Stream,
+			accel: true,
+			gyro: true,
+			temperature: true,
+			timestamp_fsync: true,
+			hires: false,
+			watermark: 0,
+		}
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FifoTimeMode {
+	Absolute,  // packet timestamps are the 16 bit free running counter
+	Delta,  // packet timestamps are the time since the previous ODR event (TMST_DELTA_EN)
+	Interpolated,  // timestamps are disabled or too short for the ODR, every sample is one ODR period after the previous one
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FifoPacket {
+	pub header: u8,
+	// 16 bit samples, or 20 bit samples when `hires`. The datasheet (DS-000347, 6.1) gives high resolution data as
+	// 18 bit accel at 8192 LSB/g and 19 bit gyro at 131 LSB/dps, with the unused low bits of the 20 bit field zero.
+	// That is 16 times the ±16 g / ±2000 dps 16 bit scale, 32768 LSB/g and 262.144 LSB/dps, whatever the range.
+	pub accel: Option<[i32; 3]>,
+	pub gyro: Option<[i32; 3]>,
+	pub temperature: Option<i16>,
+	pub timestamp: Option<u16>,
+	// Microseconds on the parser's monotonic time base.
+	pub time_us: u64,
+	// Time from the FSYNC edge to this sample in microseconds, if this is the first sample after one.
+	pub fsync_event: Option<u16>,
+	// Axes enabled in SENSOR_CONFIG0 when the packet was parsed.
+	pub accel_axes: AxisMask,
+	pub gyro_axes: AxisMask,
+}
+
+impl FifoPacket {
+	pub fn hires(&self) -> bool {
+		self.header & HEADER_20 != 0
+	}
+
+	// The timestamp field holds the FSYNC delta and this is the first ODR after an FSYNC edge.
+	pub fn fsync(&self) -> bool {
+		self.header & HEADER_TIMESTAMP_FSYNC == HEADER_FSYNC_TIMESTAMP
+	}
+
+	// The sensor's ODR differs from the previous packet.
+	pub fn accel_odr_changed(&self) -> bool {
+		self.header & HEADER_ODR_ACCEL != 0
+	}
+
+	pub fn gyro_odr_changed(&self) -> bool {
+		self.header & HEADER_ODR_GYRO != 0
+	}
+
+	// NaN for disabled axes.
+	pub fn accel_g(&self, fs: AccelFs) -> Option<[f32; 3]> {
+		let lsb_per_g = if self.hires() { AccelFs::G16.lsb_per_g() * 16.0 } else { fs.lsb_per_g() };
+		self.accel.map(|a| self.accel_axes.nan_disabled(a.map(|v| v as f32 / lsb_per_g)))
+	}
+
+	pub fn gyro_dps(&self, fs: GyroFs) -> Option<[f32; 3]> {
+		let lsb_per_dps = if self.hires() { GyroFs::Dps2000.lsb_per_dps() * 16.0 } else { fs.lsb_per_dps() };
+		self.gyro.map(|g| self.gyro_axes.nan_disabled(g.map(|v| v as f32 / lsb_per_dps)))
+	}
+
+	pub fn accel_g_axes(&self, fs: AccelFs) -> Option<[Option<f32>; 3]> {
+		self.accel_g(fs).map(|a| self.accel_axes.apply(a))
+	}
+
+	pub fn gyro_dps_axes(&self, fs: GyroFs) -> Option<[Option<f32>; 3]> {
+		self.gyro_dps(fs).map(|g| self.gyro_axes.apply(g))
+	}
+
+	// Only for 16 bit packets.
+	pub fn accel_data(&self, fs: AccelFs) -> Option<AccelData> {
+		match self.accel {
+			Some(a) if !self.hires() => Some(AccelData { raw: a.map(|v| v as i16), fs, axes: self.accel_axes }),
+			_ => None,
+		}
+	}
+
+	pub fn gyro_data(&self, fs: GyroFs) -> Option<GyroData> {
+		match self.gyro {
+			Some(g) if !self.hires() => Some(GyroData { raw: g.map(|v| v as i16), fs, axes: self.gyro_axes }),
+			_ => None,
+		}
+	}
+
+	#[cfg(feature = "fixed-point")]
+	pub fn accel_milli_g(&self, fs: AccelFs) -> Option<[i32; 3]> {
+		let (fs, shift) = if self.hires() { (AccelFs::G16, 4) } else { (fs, 0) };
+		self.accel.map(|a| a.map(|v| crate::data::milli_g(v, fs, shift)))
+	}
+
+	#[cfg(feature = "fixed-point")]
+	pub fn gyro_milli_dps(&self, fs: GyroFs) -> Option<[i32; 3]> {
+		let (fs, shift) = if self.hires() { (GyroFs::Dps2000, 4) } else { (fs, 0) };
+		self.gyro.map(|g| g.map(|v| crate::data::milli_dps(v, fs, shift)))
+	}
+
+	#[cfg(feature = "fixed-point")]
+	pub fn temperature_centi_c(&self) -> Option<i32> {
+		let lsb_per_c_x100 = if self.hires() { 13248 } else { 207 };
+		self.temperature.map(|t| crate::data::centi_celsius(t, lsb_per_c_x100))
+	}
+
+	pub fn temperature_c(&self) -> Option<f32> {
+		// 8 bit in 8 and 16 byte packets, 16 bit in 20 byte packets
+		let lsb_per_c = if self.hires() { 132.48 } else { 2.07 };
+		self.temperature.map(|t| t as f32 / lsb_per_c + 25.0)
+	}
+}
+
+// Decodes FIFO bytes into packets and reconstructs the time of every sample.
+// State carries over between reads, so one parser should see every packet drained from the device.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FifoParser {
+	time_mode: FifoTimeMode,
+	timestamp: Timestamp,
+	timestamp_config: TimestampConfig,
+	tick: TickPeriod,
+	odr: Odr,
+	period_ns: u64,
+	time_ns: Option<u64>,
+	offset_ns: i64,  // added to timestamp based times so they continue the previous time base
+	rebase: bool,  // recompute `offset_ns` at the next timestamped packet
+	endian: Endian,
+	accel_axes: AxisMask,
+	gyro_axes: AxisMask,
+	mounting: Mounting,
+}
+
+impl FifoParser {
+	// `odr` is the rate packets are written at, i.e. the faster of the enabled sensors.
+	// The 16 bit packet timestamps roll over every 65.5 ms at 1 us resolution (1.05 s at 16 us). When one ODR period
+	// is that long, the number of rollovers between two packets is unknown, so the parser interpolates instead.
+	pub fn new(timestamp_config: TimestampConfig, odr: Odr) -> Self {
+		let tick = TickPeriod::new(timestamp_config.resolution, ClockInput::Internal);
+		let period_ns = ClockInput::Internal.period_ns(odr);
+		Self {
+			time_mode: time_mode(timestamp_config, tick, period_ns),
+			timestamp: Timestamp::fifo(tick),
+			timestamp_config,
+			tick,
+			odr,
+			period_ns,
+			time_ns: None,
+			offset_ns: 0,
+			rebase: false,
+			endian: Endian::Big,
+			accel_axes: AxisMask::ALL,
+			gyro_axes: AxisMask::ALL,
+			mounting: Mounting::IDENTITY,
+		}
+	}
+
+	pub fn interpolated(odr: Odr) -> Self {
+		Self::new(TimestampConfig { enable: false, ..TimestampConfig::default() }, odr)
+	}
+
+	// Interpolate with the real ODR, and count timestamps in RTC periods, when the device runs from CLKIN.
+	pub fn with_clock(mut self, clock: ClockInput) -> Self {
+		self.period_ns = clock.period_ns(self.odr);
+		self.tick = TickPeriod::new(self.timestamp_config.resolution, clock);
+		self.timestamp = Timestamp::fifo(self.tick);
+		self.time_mode = time_mode(self.timestamp_config, self.tick, self.period_ns);
+		self
+	}
+
+	// Follow INTF_CONFIG0.SENSOR_DATA_ENDIAN, which also applies to FIFO packets.
+	pub fn with_endian(mut self, endian: Endian) -> Self {
+		self.endian = endian;
+		self
+	}
+
+	pub fn with_axes(mut self, accel: AxisMask, gyro: AxisMask) -> Self {
+		self.accel_axes = accel;
+		self.gyro_axes = gyro;
+		self
+	}
+
+	// Rotate accel and gyro into the body frame.
+	pub fn with_mounting(mut self, mounting: Mounting) -> Self {
+		self.mounting = mounting;
+		self
+	}
+
+	// Carry the time base of `previous` over, so `time_us` keeps increasing across a reconfiguration.
+	// If the timestamp format changed, the next timestamped packet is placed one period after the last one.
+	pub fn continuing(mut self, previous: &FifoParser) -> Self {
+		self.time_ns = previous.time_ns;
+		if self.time_mode == previous.time_mode && self.tick == previous.tick {
+			self.timestamp = previous.timestamp;
+			self.offset_ns = previous.offset_ns;
+			self.rebase = previous.rebase;
+		} else {
+			self.offset_ns = previous.offset_ns;
+			self.rebase = previous.time_ns.is_some();
+		}
+		self
+	}
+
+	pub fn time_mode(&self) -> FifoTimeMode {
+		self.time_mode
+	}
+
+	pub fn parse<'a>(&'a mut self, bytes: &'a [u8]) -> FifoPackets<'a> {
+		FifoPackets { bytes, parser: self }
+	}
+
+	// Forget the time history, e.g. after a FIFO flush or overflow.
+	pub fn reset(&mut self) {
+		self.timestamp.reset();
+		self.time_ns = None;
+		self.offset_ns = 0;
+		self.rebase = false;
+	}
+
+	fn stamp(&mut self, header: u8, timestamp: Option<u16>) -> u64 {
+		let odr_timestamp = match timestamp {
+			Some(t) if header & HEADER_TIMESTAMP_FSYNC == HEADER_ODR_TIMESTAMP => Some(t as u32),
+			_ => None,
+		};
+		let counter_ns = match (self.time_mode, odr_timestamp) {
+			(FifoTimeMode::Absolute, Some(t)) => Some(self.timestamp.update(t) * 1000),
+			(FifoTimeMode::Delta, Some(t)) => Some(self.timestamp.advance(t) * 1000),
+			_ => None,
+		};
+		let time_ns = match (counter_ns, self.time_ns) {
+			(Some(counter_ns), last) => {
+				if self.rebase && let Some(last) = last {
+					let next = match self.time_mode {
+						FifoTimeMode::Delta => last + counter_ns,
+						_ => last + self.period_ns,
+					};
+					self.offset_ns = next as i64 - counter_ns as i64;
+				}
+				self.rebase = false;
+				(counter_ns as i64 + self.offset_ns) as u64
+			},
+			(None, Some(last)) => last + self.period_ns,
+			(None, None) => 0,
+		};
+		self.time_ns = Some(time_ns);
+		time_ns / 1000
+	}
+}
+
+fn time_mode(timestamp_config: TimestampConfig, tick: TickPeriod, period_ns: u64) -> FifoTimeMode {
+	let rollover_ns = tick.ticks_to_ns(1 << Timestamp::FIFO_BITS);
+	match timestamp_config {
+		TimestampConfig { enable: false, .. } => FifoTimeMode::Interpolated,
+		_ if period_ns >= rollover_ns => FifoTimeMode::Interpolated,
+		TimestampConfig { delta: true, .. } => FifoTimeMode::Delta,
+		_ => FifoTimeMode::Absolute,
+	}
+}
+
+pub struct FifoPackets<'a> {
+	bytes: &'a [u8],
+	parser: &'a mut FifoParser,
+}
+
+impl Iterator for FifoPackets<'_> {
+	type Item = FifoPacket;
+
+	fn next(&mut self) -> Option<FifoPacket> {
+		let header = *self.bytes.first()?;
+		if header & HEADER_MSG != 0 {
+			self.bytes = &[];
+			return None;
+		}
+		let has_accel = header & HEADER_ACCEL != 0;
+		let has_gyro = header & HEADER_GYRO != 0;
+		let hires = header & HEADER_20 != 0;
+		let len = match (has_accel, has_gyro) {
+			_ if hires => 20,
+			(true, true) => 16,
+			(true, false) | (false, true) => 8,
+			(false, false) => 1,
+		};
+		if self.bytes.len() < len {
+			self.bytes = &[];
+			return None;
+		}
+		let (packet, rest) = self.bytes.split_at(len);
+		self.bytes = rest;
+
+		let endian = self.parser.endian;
+		let xyz = |offset: usize| -> [i32; 3] {
+			core::array::from_fn(|i| endian.i16([packet[offset + 2 * i], packet[offset + 2 * i + 1]]) as i32)
+		};
+		let (accel, gyro, temperature, timestamp) = match (has_accel, has_gyro) {
+			_ if hires => {
+				let extension = |raw: [i32; 3], shift: u32| -> [i32; 3] {
+					core::array::from_fn(|i| raw[i] << 4 | (packet[17 + i] >> shift & 0x0F) as i32)
+				};
+				(
+					Some(extension(xyz(1), 4)),
+					Some(extension(xyz(7), 0)),
+					Some(endian.i16([packet[13], packet[14]])),
+					Some(endian.u16([packet[15], packet[16]])),
+				)
+			},
+			(true, true) => (
+				Some(xyz(1)),
+				Some(xyz(7)),
+				Some(packet[13] as i8 as i16),
+				Some(endian.u16([packet[14], packet[15]])),
+			),
+			(true, false) => (Some(xyz(1)), None, Some(packet[7] as i8 as i16), None),
+			(false, true) => (None, Some(xyz(1)), Some(packet[7] as i8 as i16), None),
+			(false, false) => (None, None, None, None),
+		};
+		let mounting = self.parser.mounting;
+		let (accel, gyro) = (accel.map(|a| mounting.apply_raw(a)), gyro.map(|g| mounting.apply_raw(g)));
+		let time_us = self.parser.stamp(header, timestamp);
+		let fsync_event = match timestamp {
+			Some(delta) if header & HEADER_TIMESTAMP_FSYNC == HEADER_FSYNC_TIMESTAMP => {
+				Some(self.parser.tick.ticks_to_us(delta as u64).min(u16::MAX as u64) as u16)
+			},
+			_ => None,
+		};
+		Some(FifoPacket {
+			header,
+			accel,
+			gyro,
+			temperature,
+			timestamp,
+			time_us,
+			fsync_event,
+			accel_axes: mounting.apply_mask(self.parser.accel_axes),
+			gyro_axes: mounting.apply_mask(self.parser.gyro_axes),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const HIRES: u8 = HEADER_ACCEL | HEADER_GYRO | HEADER_20;
+
+	fn parser(odr: Odr) -> FifoParser {
+		FifoParser::new(TimestampConfig::default(), odr)
+	}
+
+	// 16 byte packet, big endian like the reset value of SENSOR_DATA_ENDIAN.
+	fn packet16(accel: [i16; 3], gyro: [i16; 3], temperature: i8, timestamp: u16) -> [u8; 16] {
+		let mut bytes = [0u8; 16];
+		bytes[0] = HEADER_ACCEL | HEADER_GYRO | HEADER_ODR_TIMESTAMP;
+		for i in 0..3 {
+			bytes[1 + 2 * i..3 + 2 * i].copy_from_slice(&accel[i].to_be_bytes());
+			bytes[7 + 2 * i..9 + 2 * i].copy_from_slice(&gyro[i].to_be_bytes());
+		}
+		bytes[13] = temperature as u8;
+		bytes[14..16].copy_from_slice(&timestamp.to_be_bytes());
+		bytes
+	}
+
+	#[test]
+	fn parses_every_packet_size() {
+		let mut bytes = [0u8; 1 + 8 + 8 + 16 + 20 + 1];
+		// 1 byte: no sensor data
+		bytes[0] = 0;
+		// 8 bytes: accel only, then gyro only
+		bytes[1..9].copy_from_slice(&[HEADER_ACCEL, 0x01, 0x00, 0xFF, 0xFF, 0x80, 0x00, 0xFE]);
+		bytes[9..17].copy_from_slice(&[HEADER_GYRO, 0x00, 0x10, 0x00, 0x20, 0x7F, 0xFF, 0x05]);
+		bytes[17..33].copy_from_slice(&packet16([1, -2, 3], [-4, 5, -6], -7, 0x1234));
+		// 20 bytes: 20 bit data with the low nibbles in bytes 17..19 (accel high, gyro low)
+		bytes[33..53].copy_from_slice(&[
+			HIRES | HEADER_ODR_TIMESTAMP,
+			0x00, 0x01, 0xFF, 0xFF, 0x80, 0x00,
+			0x00, 0x02, 0xFF, 0xFE, 0x7F, 0xFF,
+			0x01, 0x00,
+			0x12, 0x35,
+			0xA1, 0xB2, 0xC3,
+		]);
+		// the rest of the FIFO is empty
+		bytes[53] = HEADER_MSG;
+
+		let mut parser = parser(Odr::Hz1k);
+		let mut packets = parser.parse(&bytes);
+		let empty = packets.next().unwrap();
+		assert_eq!((empty.accel, empty.gyro, empty.temperature, empty.timestamp), (None, None, None, None));
+
+		let accel = packets.next().unwrap();
+		assert_eq!((accel.accel, accel.gyro, accel.temperature), (Some([256, -1, -32768]), None, Some(-2)));
+		let gyro = packets.next().unwrap();
+		assert_eq!((gyro.accel, gyro.gyro, gyro.temperature), (None, Some([16, 32, 32767]), Some(5)));
+
+		let full = packets.next().unwrap();
+		assert_eq!((full.accel, full.gyro), (Some([1, -2, 3]), Some([-4, 5, -6])));
+		assert_eq!((full.temperature, full.timestamp, full.hires()), (Some(-7), Some(0x1234), false));
+		assert_eq!(full.temperature_c(), Some(-7.0 / 2.07 + 25.0));
+
+		let hires = packets.next().unwrap();
+		assert!(hires.hires());
+		assert_eq!(hires.accel, Some([0x1A, -0x10 | 0xB, -0x8_0000 | 0xC]));
+		assert_eq!(hires.gyro, Some([0x21, -0x20 | 0x2, 0x7_FFF3]));
+		assert_eq!((hires.temperature, hires.timestamp), (Some(0x100), Some(0x1235)));
+		assert_eq!(hires.time_us - full.time_us, 1);
+		assert!(packets.next().is_none());
+	}
+
+	#[test]
+	fn truncated_packet_ends_the_read() {
+		let bytes = packet16([0; 3], [0; 3], 0, 0);
+		let mut parser = parser(Odr::Hz1k);
+		assert_eq!(parser.parse(&bytes[..15]).count(), 0);
+		assert_eq!(parser.parse(&bytes).count(), 1);
+	}
+
+	#[test]
+	fn little_endian_data() {
+		let mut bytes = packet16([0x0102, 0, 0], [0; 3], 0, 0x0304);
+		bytes[1..3].reverse();
+		bytes[14..16].reverse();
+		let mut parser = parser(Odr::Hz1k).with_endian(Endian::Little);
+		let packet = parser.parse(&bytes).next().unwrap();
+		assert_eq!((packet.accel.unwrap()[0], packet.timestamp), (0x0102, Some(0x0304)));
+	}
+
+	#[test]
+	fn absolute_timestamps_unwrap_across_rollover() {
+		let mut parser = parser(Odr::Hz1k);
+		assert_eq!(parser.time_mode(), FifoTimeMode::Absolute);
+		let mut times = [0; 4];
+		for (k, time) in times.iter_mut().enumerate() {
+			let timestamp = (0xFC00 + 1000 * k as u32) as u16;
+			*time = parser.parse(&packet16([0; 3], [0; 3], 0, timestamp)).next().unwrap().time_us;
+		}
+		assert_eq!(times, [0xFC00, 0xFC00 + 1000, 0xFC00 + 2000, 0xFC00 + 3000]);
+	}
+
+	#[test]
+	fn delta_timestamps_accumulate() {
+		let mut parser = FifoParser::new(TimestampConfig { delta: true, ..TimestampConfig::default() }, Odr::Hz1k);
+		assert_eq!(parser.time_mode(), FifoTimeMode::Delta);
+		let mut bytes = [0u8; 48];
+		for (k, chunk) in bytes.chunks_mut(16).enumerate() {
+			chunk.copy_from_slice(&packet16([0; 3], [0; 3], 0, 990 + 10 * k as u16));
+		}
+		let times: [u64; 3] = {
+			let mut packets = parser.parse(&bytes);
+			core::array::from_fn(|_| packets.next().unwrap().time_us)
+		};
+		assert_eq!(times, [990, 1990, 3000]);
+	}
+
+	#[test]
+	fn reconfiguration_continues_the_time_base() {
+		let mut parser = parser(Odr::Hz1k);
+		assert_eq!(parser.parse(&packet16([0; 3], [0; 3], 0, 5000)).next().unwrap().time_us, 5000);
+		let delta = TimestampConfig { delta: true, ..TimestampConfig::default() };
+		let mut parser = FifoParser::new(delta, Odr::Hz1k).continuing(&parser);
+		assert_eq!(parser.parse(&packet16([0; 3], [0; 3], 0, 1000)).next().unwrap().time_us, 6000);
+		parser.reset();
+		assert_eq!(parser.parse(&packet16([0; 3], [0; 3], 0, 1000)).next().unwrap().time_us, 1000);
+	}
+
+	#[test]
+	fn slow_odr_falls_back_to_interpolation() {
+		// 80 ms between packets, longer than the 65.5 ms rollover of 1 us timestamps
+		let mut parser = parser(Odr::Hz12_5);
+		assert_eq!(parser.time_mode(), FifoTimeMode::Interpolated);
+		let mut times = [0; 3];
+		for (k, time) in times.iter_mut().enumerate() {
+			let timestamp = (80_000 * k as u32) as u16;
+			*time = parser.parse(&packet16([0; 3], [0; 3], 0, timestamp)).next().unwrap().time_us;
+		}
+		assert_eq!(times, [0, 80_000, 160_000]);
+
+		// 16 us ticks roll over after 1.05 s
+		let coarse = TimestampConfig { resolution: crate::register::bank0::TmstRes::Us16, ..TimestampConfig::default() };
+		assert_eq!(FifoParser::new(coarse, Odr::Hz12_5).time_mode(), FifoTimeMode::Absolute);
+	}
+
+	#[cfg(feature = "fixed-point")]
+	mod fixed_point {
+		use super::*;
+
+		const HIRES_MAX: i32 = (1 << 19) - 1;
+
+		// Half away from zero. The references below are exact in f64, so half-way values stay half-way.
+		fn round(v: f64) -> i32 {
+			(if v < 0.0 { v - 0.5 } else { v + 0.5 }) as i32
+		}
+
+		fn packet(header: u8, accel: i32, gyro: i32, temperature: i16) -> FifoPacket {
+			FifoPacket {
+				header,
+				accel: Some([accel, 0, 0]),
+				gyro: Some([gyro, 0, 0]),
+				temperature: Some(temperature),
+				timestamp: None,
+				time_us: 0,
+				fsync_event: None,
+				accel_axes: AxisMask::ALL,
+				gyro_axes: AxisMask::ALL,
+			}
+		}
+
+		#[test]
+		fn hires_milli_g_and_milli_dps_match_float_path() {
+			for raw in -HIRES_MAX - 1..=HIRES_MAX {
+				// the configured range doesn't apply to 20 bit data
+				let packet = packet(HIRES, raw, raw, 0);
+				let milli_g = packet.accel_milli_g(AccelFs::G2).unwrap()[0];
+				assert_eq!(milli_g, round(packet.accel_g(AccelFs::G2).unwrap()[0] as f64 * 1000.0), "{raw}");
+				let milli_dps = packet.gyro_milli_dps(GyroFs::Dps250).unwrap()[0];
+				assert_eq!(milli_dps, round(raw as f64 * 2_000_000.0 / (32768 * 16) as f64), "{raw}");
+				assert!((milli_dps - round(packet.gyro_dps(GyroFs::Dps250).unwrap()[0] as f64 * 1000.0)).abs() <= 1, "{raw}");
+			}
+		}
+
+		#[test]
+		fn hires_extremes() {
+			let min = packet(HIRES, -HIRES_MAX - 1, -HIRES_MAX - 1, i16::MIN);
+			assert_eq!(min.accel_milli_g(AccelFs::G16).unwrap()[0], -16000);
+			assert_eq!(min.gyro_milli_dps(GyroFs::Dps2000).unwrap()[0], -2_000_000);
+			let max = packet(HIRES, HIRES_MAX, HIRES_MAX, i16::MAX);
+			assert_eq!(max.accel_milli_g(AccelFs::G16).unwrap()[0], 16000);
+			assert_eq!(max.gyro_milli_dps(GyroFs::Dps2000).unwrap()[0], 1_999_996);
+		}
+
+		#[test]
+		fn hires_temperature_matches_float_path() {
+			for raw in i16::MIN..=i16::MAX {
+				let packet = packet(HIRES, 0, 0, raw);
+				let centi_c = packet.temperature_centi_c().unwrap();
+				assert_eq!(centi_c, round(raw as f64 * 10000.0 / 13248.0 + 2500.0), "{raw}");
+				assert!((centi_c - round(packet.temperature_c().unwrap() as f64 * 100.0)).abs() <= 1, "{raw}");
+			}
+		}
+
+		#[test]
+		fn fifo_temperature_matches_float_path() {
+			for raw in i8::MIN..=i8::MAX {
+				let packet = packet(HEADER_ACCEL | HEADER_GYRO, 0, 0, raw as i16);
+				let centi_c = packet.temperature_centi_c().unwrap();
+				assert_eq!(centi_c, round(raw as f64 * 10000.0 / 207.0 + 2500.0), "{raw}");
+				assert!((centi_c - round(packet.temperature_c().unwrap() as f64 * 100.0)).abs() <= 1, "{raw}");
+			}
+		}
+
+		#[test]
+		fn packet_milli_g_follows_the_configured_range() {
+			for fs in [AccelFs::G16, AccelFs::G8, AccelFs::G4, AccelFs::G2] {
+				for raw in [i16::MIN, -1024, -1, 0, 1024, i16::MAX] {
+					let packet = packet(HEADER_ACCEL | HEADER_GYRO, raw as i32, 0, 0);
+					let data = AccelData { raw: [raw, 0, 0], fs, axes: AxisMask::ALL };
+					assert_eq!(packet.accel_milli_g(fs).unwrap()[0], data.milli_g()[0]);
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,42 @@
+use crate::register::bank0::Endian;
+
+// Byte order and FIFO count format of the host interface (INTF_CONFIG0).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InterfaceConfig {
+	pub sensor_data_endian: Endian,  // data registers, TMST_FSYNC and FIFO packets
+	pub fifo_count_endian: Endian,
+	pub fifo_count_records: bool,  // FIFO_COUNT and the watermark count packets instead of bytes
+	pub fifo_hold_last_data: bool,  // invalid samples repeat the last valid value instead of -32768
+}
+
+impl InterfaceConfig {
+	// power-on reset value
+	pub const RESET: InterfaceConfig = InterfaceConfig {
+		sensor_data_endian: Endian::Big,
+		fifo_count_endian: Endian::Big,
+		fifo_count_records: false,
+		fifo_hold_last_data: false,
+	};
+}
+
+impl Default for InterfaceConfig {
+	fn default() -> Self {
+		Self::RESET
+	}
+}
+
+// In-band interrupt options of the I3C interface (INTF_CONFIG6).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct I3cSettings {
+	pub ibi: bool,  // signal interrupts in band instead of on INT1
+	pub ibi_payload: bool,  // send a payload byte with every IBI
+	pub ddr: bool,
+}
+
+impl Default for I3cSettings {
+	fn default() -> Self {
+		Self { ibi: true, ibi_payload: false, ddr: false }
+	}
+}
@@ -0,0 +1,83 @@
+use crate::apex::{R2wEvent, TiltEvent};
+use crate::register::bank0::{IntStatus2, IntStatus3};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum InterruptPin {
+	Int1,
+	Int2,  // pin 9 when INTF_CONFIG5.PIN9_FUNCTION is INT2
+}
+
+// Decoded INT_STATUS2. Reading the register clears it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MotionStatus {
+	pub smd: bool,
+	pub wom_x: bool,
+	pub wom_y: bool,
+	pub wom_z: bool,
+}
+
+impl MotionStatus {
+	pub fn wom(&self) -> bool {
+		self.wom_x || self.wom_y || self.wom_z
+	}
+}
+
+impl From<IntStatus2> for MotionStatus {
+	fn from(register: IntStatus2) -> Self {
+		let (smd, wom_z, wom_y, wom_x) = register.depack();
+		Self { smd, wom_x, wom_y, wom_z }
+	}
+}
+
+// Decoded INT_STATUS3. Reading the register clears it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ApexStatus {
+	pub step_detected: bool,
+	pub step_count_overflow: bool,
+	pub tilt: bool,
+	pub wake: bool,
+	pub sleep: bool,
+	pub tap: bool,
+}
+
+impl ApexStatus {
+	pub fn tilt_event(&self) -> Option<TiltEvent> {
+		self.tilt.then_some(TiltEvent)
+	}
+
+	// Wake and sleep can both be pending. The device doesn't report which came first.
+	pub fn r2w_events(&self) -> impl Iterator<Item = R2wEvent> {
+		[self.wake.then_some(R2wEvent::Wake), self.sleep.then_some(R2wEvent::Sleep)].into_iter().flatten()
+	}
+}
+
+impl From<IntStatus3> for ApexStatus {
+	fn from(register: IntStatus3) -> Self {
+		let (step_detected, step_count_overflow, tilt, wake, sleep, tap) = register.depack();
+		Self { step_detected, step_count_overflow, tilt, wake, sleep, tap }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn motion_status_bits() {
+		let status = MotionStatus::from(IntStatus2(0b1010));
+		assert_eq!(status, MotionStatus { smd: true, wom_x: false, wom_y: true, wom_z: false });
+		assert!(status.wom());
+		assert!(!MotionStatus::from(IntStatus2(0b1000)).wom());
+	}
+
+	#[test]
+	fn pending_raise_to_wake_events() {
+		let both = ApexStatus::from(IntStatus3(0b0110));
+		assert!(both.r2w_events().eq([R2wEvent::Wake, R2wEvent::Sleep]));
+		assert!(ApexStatus::from(IntStatus3(0b0010)).r2w_events().eq([R2wEvent::Sleep]));
+		assert_eq!(ApexStatus::from(IntStatus3(0b10_1001)).r2w_events().count(), 0);
+	}
+}
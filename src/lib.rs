@@ -1,9 +1,26 @@
 #![no_std]
 
 mod type_number;
-mod register;
+pub mod register;
+pub mod transport;
+pub mod data;
+pub mod calibration;
+pub mod timestamp;
+pub mod fifo;
+pub mod clock;
+pub mod interrupt;
+pub mod motion;
+pub mod apex;
+pub mod interface;
+pub mod array;
+pub mod mounting;
+mod error;
 
-// #[cfg(feature = "async")]
-// mod async_version;
+pub use error::Error;
+
+#[cfg(feature = "async")]
+mod async_version;
+#[cfg(feature = "async")]
+pub use async_version::{Icm42688, ImuArray, SignificantMotion, WakeOnMotion};
 // #[cfg(feature = "blocking")]
 // mod sync_version;
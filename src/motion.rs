@@ -0,0 +1,65 @@
+use crate::interrupt::InterruptPin;
+use crate::register::bank0::{AccelLpClkSel, Odr, SmdMode, WomIntMode, WomMode};
+
+// WoM thresholds are programmed in 1/256 g steps (3.9 mg).
+pub fn wom_threshold_lsb(threshold_mg: u16) -> u8 {
+	((threshold_mg as u32 * 256 + 500) / 1000).min(u8::MAX as u32) as u8
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct WomSettings {
+	pub thresholds_mg: [u16; 3],
+	pub mode: WomMode,
+	pub int_mode: WomIntMode,
+	pub pin: InterruptPin,
+	pub odr: Odr,  // accel runs in low power mode at this rate, see `Odr::low_power`
+	pub lp_clock: AccelLpClkSel,  // INTF_CONFIG1.ACCEL_LP_CLK_SEL
+}
+
+impl WomSettings {
+	// Same threshold on every axis, any axis wakes, routed to INT1 at 50 Hz from the wake-up oscillator
+	// as recommended by the datasheet.
+	pub fn new(threshold_mg: u16, mode: WomMode) -> Self {
+		Self {
+			thresholds_mg: [threshold_mg; 3],
+			mode,
+			int_mode: WomIntMode::Or,
+			pin: InterruptPin::Int1,
+			odr: Odr::Hz50,
+			lp_clock: AccelLpClkSel::WakeUpOscillator,
+		}
+	}
+}
+
+// Time between the two WoM events that make up a significant motion.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SmdWindow {
+	Short,  // 1 s
+	Long,  // 3 s
+}
+
+impl From<SmdWindow> for SmdMode {
+	fn from(window: SmdWindow) -> Self {
+		match window {
+			SmdWindow::Short => SmdMode::Short,
+			SmdWindow::Long => SmdMode::Long,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wom_threshold_rounds_to_the_nearest_step() {
+		assert_eq!(wom_threshold_lsb(0), 0);
+		assert_eq!(wom_threshold_lsb(1), 0);
+		assert_eq!(wom_threshold_lsb(2), 1);
+		assert_eq!(wom_threshold_lsb(98), 25);
+		assert_eq!(wom_threshold_lsb(996), 255);
+		assert_eq!(wom_threshold_lsb(u16::MAX), 255);
+	}
+}
@@ -0,0 +1,171 @@
+use crate::calibration::round;
+use crate::data::AxisMask;
+use crate::register::bank4::MountingOrientation;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SignedAxis {
+	PosX,
+	NegX,
+	PosY,
+	NegY,
+	PosZ,
+	NegZ,
+}
+
+impl SignedAxis {
+	pub const ALL: [SignedAxis; 6] = [
+		SignedAxis::PosX,
+		SignedAxis::NegX,
+		SignedAxis::PosY,
+		SignedAxis::NegY,
+		SignedAxis::PosZ,
+		SignedAxis::NegZ,
+	];
+
+	pub fn vector(self) -> [i8; 3] {
+		match self {
+			SignedAxis::PosX => [1, 0, 0],
+			SignedAxis::NegX => [-1, 0, 0],
+			SignedAxis::PosY => [0, 1, 0],
+			SignedAxis::NegY => [0, -1, 0],
+			SignedAxis::PosZ => [0, 0, 1],
+			SignedAxis::NegZ => [0, 0, -1],
+		}
+	}
+
+	fn from_vector(v: [i8; 3]) -> Option<SignedAxis> {
+		SignedAxis::ALL.into_iter().find(|a| a.vector() == v)
+	}
+}
+
+// A right-angle mounting, given by where the sensor's x and y axes point in the body frame.
+// The sensor's z axis follows from the right hand rule, so there are 24 of them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AxisRotation {
+	x: SignedAxis,
+	y: SignedAxis,
+}
+
+impl AxisRotation {
+	pub const IDENTITY: AxisRotation = AxisRotation { x: SignedAxis::PosX, y: SignedAxis::PosY };
+
+	// `None` when `x` and `y` are parallel.
+	pub fn new(x: SignedAxis, y: SignedAxis) -> Option<AxisRotation> {
+		let (a, b) = (x.vector(), y.vector());
+		let dot = a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+		(dot == 0).then_some(AxisRotation { x, y })
+	}
+
+	pub fn all() -> [AxisRotation; 24] {
+		let mut all = [AxisRotation::IDENTITY; 24];
+		let rotations = SignedAxis::ALL.into_iter()
+			.flat_map(|x| SignedAxis::ALL.into_iter().filter_map(move |y| AxisRotation::new(x, y)));
+		for (slot, rotation) in all.iter_mut().zip(rotations) {
+			*slot = rotation;
+		}
+		all
+	}
+
+	pub fn x(self) -> SignedAxis {
+		self.x
+	}
+
+	pub fn y(self) -> SignedAxis {
+		self.y
+	}
+
+	pub fn z(self) -> SignedAxis {
+		let (a, b) = (self.x.vector(), self.y.vector());
+		let cross = [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]];
+		SignedAxis::from_vector(cross).unwrap()
+	}
+
+	// body = matrix * sensor, the columns are the sensor axes in the body frame.
+	pub fn matrix(self) -> [[i8; 3]; 3] {
+		let columns = [self.x.vector(), self.y.vector(), self.z().vector()];
+		core::array::from_fn(|row| core::array::from_fn(|column| columns[column][row]))
+	}
+}
+
+// Orientation of the sensor on the board, used to report samples in the body frame.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Mounting {
+	Rotation(AxisRotation),
+	Matrix([[f32; 3]; 3]),  // body = matrix * sensor, a rotation
+}
+
+impl Mounting {
+	pub const IDENTITY: Mounting = Mounting::Rotation(AxisRotation::IDENTITY);
+
+	pub fn matrix(&self) -> [[f32; 3]; 3] {
+		match self {
+			Mounting::Rotation(rotation) => rotation.matrix().map(|row| row.map(|v| v as f32)),
+			Mounting::Matrix(matrix) => *matrix,
+		}
+	}
+
+	pub fn apply(&self, v: [f32; 3]) -> [f32; 3] {
+		self.matrix().map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+	}
+
+	// A linear map on sensor axes expressed on body axes, `matrix * a * matrix^T`.
+	pub fn apply_to_map(&self, a: [[f32; 3]; 3]) -> [[f32; 3]; 3] {
+		let m = self.matrix();
+		let ma: [[f32; 3]; 3] = core::array::from_fn(|i| core::array::from_fn(|j| (0..3).map(|k| m[i][k] * a[k][j]).sum()));
+		core::array::from_fn(|i| core::array::from_fn(|j| (0..3).map(|k| ma[i][k] * m[j][k]).sum()))
+	}
+
+	// Raw sensor counts. Exact for right-angle rotations, rounded for arbitrary matrices.
+	pub fn apply_raw(&self, v: [i32; 3]) -> [i32; 3] {
+		match self {
+			Mounting::Rotation(rotation) => {
+				rotation.matrix().map(|row| (0..3).map(|i| row[i] as i32 * v[i]).sum())
+			},
+			Mounting::Matrix(_) => {
+				let rotated = self.apply(v.map(|v| v as f32));
+				// keep the 20 bit range of high resolution FIFO data
+				rotated.map(|v| {
+					let r = if v < 0.0 { v - 0.5 } else { v + 0.5 };
+					r.clamp(i32::MIN as f32, i32::MAX as f32) as i32
+				})
+			},
+		}
+	}
+
+	pub fn apply_i16(&self, v: [i16; 3]) -> [i16; 3] {
+		self.apply_raw(v.map(|v| v as i32)).map(|v| v.clamp(i16::MIN as i32, i16::MAX as i32) as i16)
+	}
+
+	// A body axis is valid when every sensor axis it is made of is enabled.
+	pub fn apply_mask(&self, mask: AxisMask) -> AxisMask {
+		let enabled = mask.to_array();
+		let [x, y, z] = self.matrix().map(|row| (0..3).all(|i| row[i] == 0.0 || enabled[i]));
+		AxisMask { x, y, z }
+	}
+
+	// The APEX_CONFIG5.MOUNTING_MATRIX setting with the same orientation. The DMP only knows 8 of them.
+	pub fn apex_orientation(&self) -> Option<MountingOrientation> {
+		let matrix = self.matrix();
+		let exact = matrix.iter().flatten().all(|v| (*v - round(*v) as f32).abs() < 1e-3);
+		if !exact {
+			return None;
+		}
+		let matrix = matrix.map(|row| row.map(|v| round(v) as i8));
+		MountingOrientation::ALL.into_iter().find(|o| o.matrix() == matrix)
+	}
+}
+
+impl Default for Mounting {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl From<AxisRotation> for Mounting {
+	fn from(rotation: AxisRotation) -> Self {
+		Mounting::Rotation(rotation)
+	}
+}
@@ -1,3 +1,7 @@
 mod constrainer;
 mod register_map;
-mod spi_constraints;
\ No newline at end of file
+mod spi_constraints;
+
+pub use constrainer::Constrainer;
+pub use register_map::*;
+pub use spi_constraints::*;
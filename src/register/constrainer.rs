@@ -1,5 +1,64 @@
+use core::marker::PhantomData;
+
+use crate::error::Error;
+use crate::transport::Transport;
 use crate::type_number::{U8T, U8};
 
-struct Constrainer<BANK_NUM: U8T> {
-	
-}
\ No newline at end of file
+use super::register_map::{Address, RegBankSel};
+use super::spi_constraints::{ByteReadable, MultiReadable, WordReadable, Writable};
+
+// Register access typed by the currently selected bank.
+// Only registers implementing `Address<N>` can be touched while the device is in bank N.
+pub struct Constrainer<'a, T, Bank: U8T> {
+	transport: &'a mut T,
+	_bank: PhantomData<Bank>,
+}
+
+impl<'a, T: Transport> Constrainer<'a, T, U8<0>> {
+	// The caller must know that the device is in bank 0. The driver keeps it there between calls.
+	pub fn bank0(transport: &'a mut T) -> Self {
+		Self { transport, _bank: PhantomData }
+	}
+}
+
+impl<'a, T: Transport, const N: u8> Constrainer<'a, T, U8<N>> {
+	pub async fn read<R: Address<N> + ByteReadable>(&mut self) -> Result<R, Error<T::Error>> {
+		let mut buf = [0u8; 1];
+		self.transport.read(R::ADDR, &mut buf).await.map_err(Error::Transport)?;
+		Ok(R::from(buf[0]))
+	}
+
+	pub async fn write<R: Address<N> + Writable>(&mut self, register: R) -> Result<(), Error<T::Error>> {
+		self.transport.write(R::ADDR, register.into()).await.map_err(Error::Transport)
+	}
+
+	pub async fn modify<R: Address<N> + ByteReadable + Writable>(&mut self, f: impl FnOnce(R) -> R) -> Result<(), Error<T::Error>> {
+		let register = self.read::<R>().await?;
+		self.write(f(register)).await
+	}
+
+	pub async fn read_word<R: Address<N> + WordReadable>(&mut self) -> Result<R, Error<T::Error>> {
+		let mut buf = [0u8; 2];
+		self.transport.read(R::ADDR, &mut buf).await.map_err(Error::Transport)?;
+		Ok(R::from(buf))
+	}
+
+	// Reads `buf.len()` bytes starting at `R` in a single transaction, so that contiguous data registers come from the same sample.
+	pub async fn read_burst<R: Address<N> + WordReadable>(&mut self, buf: &mut [u8]) -> Result<(), Error<T::Error>> {
+		self.transport.read(R::ADDR, buf).await.map_err(Error::Transport)
+	}
+
+	pub async fn read_multi<R: Address<N> + MultiReadable>(&mut self, buf: &mut [u8]) -> Result<(), Error<T::Error>> {
+		self.transport.read(R::ADDR, buf).await.map_err(Error::Transport)
+	}
+
+	// `buf[0]` is used for the address, the data is read into `buf[1..]`.
+	pub async fn read_multi_in_place<R: Address<N> + MultiReadable>(&mut self, buf: &mut [u8]) -> Result<(), Error<T::Error>> {
+		self.transport.read_in_place(R::ADDR, buf).await.map_err(Error::Transport)
+	}
+
+	pub async fn select_bank<const M: u8>(self) -> Result<Constrainer<'a, T, U8<M>>, Error<T::Error>> {
+		self.transport.write(<RegBankSel as Address<N>>::ADDR, RegBankSel::pack(M).into()).await.map_err(Error::Transport)?;
+		Ok(Constrainer { transport: self.transport, _bank: PhantomData })
+	}
+}
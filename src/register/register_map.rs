@@ -1,18 +1,67 @@
-trait Address<const BANK_NUM: u8> {
+use super::spi_constraints::{ByteReadable, MultiReadable, WordReadable, Writable};
+
+pub trait Address<const BANK_NUM: u8> {
 	const ADDR: u8;
 }
 
+macro_rules! byte_register {
+	($name:ident) => {
+		impl From<u8> for $name {
+			fn from(value: u8) -> Self {
+				Self(value)
+			}
+		}
+		impl From<$name> for u8 {
+			fn from(value: $name) -> Self {
+				value.0
+			}
+		}
+	};
+}
+
+macro_rules! word_register {
+	($name:ident) => {
+		impl From<[u8; 2]> for $name {
+			fn from(value: [u8; 2]) -> Self {
+				Self(value)
+			}
+		}
+	};
+}
+
+// REG_BANK_SEL is mapped at the same address in every bank.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct RegBankSel(pub u8);
+impl RegBankSel {
+	pub fn pack(bank_sel: u8) -> RegBankSel {
+		RegBankSel(bank_sel & 0b111)
+	}
+
+	pub fn depack(&self) -> u8 {
+		self.0 & 0b111
+	}
+}
+impl<const BANK_NUM: u8> Address<BANK_NUM> for RegBankSel {
+	const ADDR: u8 = 0x76;
+}
+byte_register!(RegBankSel);
+impl ByteReadable for RegBankSel {}
+impl Writable for RegBankSel {}
+
 pub mod bank0 {
 	use super::*;
 
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 	pub struct DeviceConfig(pub u8);
 	#[derive(Clone, Copy, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 	#[repr(u8)]
 	pub enum SpiMode {
 		Mode0Mode3 = 0,
 		Mode1Mode2 = 1,
 	}
 	#[derive(Clone, Copy, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 	#[repr(u8)]
 	pub enum SoftResetConfig {
 		Normal = 0,
@@ -31,8 +80,1488 @@ pub mod bank0 {
 		}
 	}
 	impl Address<0> for DeviceConfig {
-		const ADDR: u8 = 0x75;
+		const ADDR: u8 = 0x11;
 	}
+	byte_register!(DeviceConfig);
 	impl ByteReadable for DeviceConfig {}
 	impl Writable for DeviceConfig {}
-}
\ No newline at end of file
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct DriveConfig(pub u8);
+	// Output edge time of the interface pins.
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum SlewRate {
+		Ns20To60 = 0b000,
+		Ns12To36 = 0b001,
+		Ns6To18 = 0b010,
+		Ns4To12 = 0b011,
+		Ns2To6 = 0b100,
+		Under2Ns = 0b101,
+		Reserved110 = 0b110,
+		Reserved111 = 0b111,
+	}
+	impl DriveConfig {
+		// bits 7:6 are reserved and must be preserved
+		pub const MASK: u8 = 0b11_1111;
+
+		pub fn pack(i2c_slew_rate: SlewRate, spi_slew_rate: SlewRate) -> DriveConfig {
+			DriveConfig((i2c_slew_rate as u8) << 3 | (spi_slew_rate as u8))
+		}
+
+		pub fn depack(&self) -> (SlewRate, SlewRate) {
+			(
+				SlewRate::n(self.0 >> 3 & 0b111).unwrap(),
+				SlewRate::n(self.0 & 0b111).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for DriveConfig {
+		const ADDR: u8 = 0x13;
+	}
+	byte_register!(DriveConfig);
+	impl ByteReadable for DriveConfig {}
+	impl Writable for DriveConfig {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoConfig(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum FifoMode {
+		Bypass = 0b00,
+		Stream = 0b01,
+		StopOnFull = 0b10,
+		StopOnFull11 = 0b11,
+	}
+	impl FifoConfig {
+		pub fn pack(fifo_mode: FifoMode) -> FifoConfig {
+			FifoConfig((fifo_mode as u8) << 6)
+		}
+
+		pub fn depack(&self) -> FifoMode {
+			FifoMode::n(self.0 >> 6 & 0b11).unwrap()
+		}
+	}
+	impl Address<0> for FifoConfig {
+		const ADDR: u8 = 0x16;
+	}
+	byte_register!(FifoConfig);
+	impl ByteReadable for FifoConfig {}
+	impl Writable for FifoConfig {}
+
+	// TEMP_DATA1 .. GYRO_DATA_Z0 are contiguous (0x1D..=0x2A) and read in one burst.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TempData(pub [u8; 2]);
+	impl Address<0> for TempData {
+		const ADDR: u8 = 0x1D;
+	}
+	word_register!(TempData);
+	impl WordReadable for TempData {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelDataX(pub [u8; 2]);
+	impl Address<0> for AccelDataX {
+		const ADDR: u8 = 0x1F;
+	}
+	word_register!(AccelDataX);
+	impl WordReadable for AccelDataX {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelDataY(pub [u8; 2]);
+	impl Address<0> for AccelDataY {
+		const ADDR: u8 = 0x21;
+	}
+	word_register!(AccelDataY);
+	impl WordReadable for AccelDataY {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelDataZ(pub [u8; 2]);
+	impl Address<0> for AccelDataZ {
+		const ADDR: u8 = 0x23;
+	}
+	word_register!(AccelDataZ);
+	impl WordReadable for AccelDataZ {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct GyroDataX(pub [u8; 2]);
+	impl Address<0> for GyroDataX {
+		const ADDR: u8 = 0x25;
+	}
+	word_register!(GyroDataX);
+	impl WordReadable for GyroDataX {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct GyroDataY(pub [u8; 2]);
+	impl Address<0> for GyroDataY {
+		const ADDR: u8 = 0x27;
+	}
+	word_register!(GyroDataY);
+	impl WordReadable for GyroDataY {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct GyroDataZ(pub [u8; 2]);
+	impl Address<0> for GyroDataZ {
+		const ADDR: u8 = 0x29;
+	}
+	word_register!(GyroDataZ);
+	impl WordReadable for GyroDataZ {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TmstFsync(pub [u8; 2]);
+	impl Address<0> for TmstFsync {
+		const ADDR: u8 = 0x2B;
+	}
+	word_register!(TmstFsync);
+	impl WordReadable for TmstFsync {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoCount(pub [u8; 2]);
+	impl Address<0> for FifoCount {
+		const ADDR: u8 = 0x2E;
+	}
+	word_register!(FifoCount);
+	impl WordReadable for FifoCount {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoData;
+	impl Address<0> for FifoData {
+		const ADDR: u8 = 0x30;
+	}
+	impl MultiReadable for FifoData {}
+
+	// APEX_DATA0 (7:0) and APEX_DATA1 (15:8), the pedometer step count.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct StepCnt(pub [u8; 2]);
+	impl StepCnt {
+		pub fn depack(&self) -> u16 {
+			u16::from_le_bytes(self.0)
+		}
+	}
+	impl Address<0> for StepCnt {
+		const ADDR: u8 = 0x31;
+	}
+	word_register!(StepCnt);
+	impl WordReadable for StepCnt {}
+
+	// Step cadence in samples per step, u6.2 fixed point.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexData2(pub u8);
+	impl Address<0> for ApexData2 {
+		const ADDR: u8 = 0x33;
+	}
+	byte_register!(ApexData2);
+	impl ByteReadable for ApexData2 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexData3(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum ActivityClass {
+		Unknown = 0b00,
+		Walk = 0b01,
+		Run = 0b10,
+		Reserved = 0b11,
+	}
+	impl ApexData3 {
+		pub fn depack(&self) -> (bool, ActivityClass) {
+			(
+				self.0 >> 2 & 0b1 != 0,  // DMP_IDLE
+				ActivityClass::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for ApexData3 {
+		const ADDR: u8 = 0x34;
+	}
+	byte_register!(ApexData3);
+	impl ByteReadable for ApexData3 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexData4(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TapNum {
+		None = 0b00,
+		Single = 0b01,
+		Double = 0b10,
+		Reserved = 0b11,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TapAxis {
+		X = 0b00,
+		Y = 0b01,
+		Z = 0b10,
+		Reserved = 0b11,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TapDir {
+		Positive = 0,
+		Negative = 1,
+	}
+	impl ApexData4 {
+		pub fn depack(&self) -> (TapNum, TapAxis, TapDir) {
+			(
+				TapNum::n(self.0 >> 3 & 0b11).unwrap(),
+				TapAxis::n(self.0 >> 1 & 0b11).unwrap(),
+				TapDir::n(self.0 & 0b1).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for ApexData4 {
+		const ADDR: u8 = 0x35;
+	}
+	byte_register!(ApexData4);
+	impl ByteReadable for ApexData4 {}
+
+	// Time between the two taps of a double tap, 16 accel samples per LSB.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexData5(pub u8);
+	impl ApexData5 {
+		pub fn depack(&self) -> u8 {
+			self.0 & 0b11_1111
+		}
+	}
+	impl Address<0> for ApexData5 {
+		const ADDR: u8 = 0x36;
+	}
+	byte_register!(ApexData5);
+	impl ByteReadable for ApexData5 {}
+
+	// APEX_DATA4 and APEX_DATA5 in one read, so the tap count and the double tap timing belong to the same tap.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TapData(pub [u8; 2]);
+	impl TapData {
+		pub fn depack(&self) -> (ApexData4, ApexData5) {
+			(ApexData4(self.0[0]), ApexData5(self.0[1]))
+		}
+	}
+	impl Address<0> for TapData {
+		const ADDR: u8 = 0x35;
+	}
+	word_register!(TapData);
+	impl WordReadable for TapData {}
+
+	// Cleared on read.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntStatus2(pub u8);
+	impl IntStatus2 {
+		pub fn depack(&self) -> (bool, bool, bool, bool) {
+			(
+				self.0 >> 3 & 0b1 != 0,  // SMD_INT
+				self.0 >> 2 & 0b1 != 0,  // WOM_Z_INT
+				self.0 >> 1 & 0b1 != 0,  // WOM_Y_INT
+				self.0 & 0b1 != 0,  // WOM_X_INT
+			)
+		}
+	}
+	impl Address<0> for IntStatus2 {
+		const ADDR: u8 = 0x37;
+	}
+	byte_register!(IntStatus2);
+	impl ByteReadable for IntStatus2 {}
+
+	// Cleared on read.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntStatus3(pub u8);
+	impl IntStatus3 {
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 5 & 0b1 != 0,  // STEP_DET_INT
+				self.0 >> 4 & 0b1 != 0,  // STEP_CNT_OVF_INT
+				self.0 >> 3 & 0b1 != 0,  // TILT_DET_INT
+				self.0 >> 2 & 0b1 != 0,  // WAKE_INT
+				self.0 >> 1 & 0b1 != 0,  // SLEEP_INT
+				self.0 & 0b1 != 0,  // TAP_DET_INT
+			)
+		}
+	}
+	impl Address<0> for IntStatus3 {
+		const ADDR: u8 = 0x38;
+	}
+	byte_register!(IntStatus3);
+	impl ByteReadable for IntStatus3 {}
+
+	// Strobe bits, they read back as 0.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct SignalPathReset(pub u8);
+	impl SignalPathReset {
+		pub fn pack(dmp_init_en: bool, dmp_mem_reset_en: bool, abort_and_reset: bool, tmst_strobe: bool, fifo_flush: bool) -> SignalPathReset {
+			SignalPathReset((dmp_init_en as u8) << 6 | (dmp_mem_reset_en as u8) << 5 | (abort_and_reset as u8) << 3 | (tmst_strobe as u8) << 2 | (fifo_flush as u8) << 1)
+		}
+	}
+	impl Address<0> for SignalPathReset {
+		const ADDR: u8 = 0x4B;
+	}
+	byte_register!(SignalPathReset);
+	impl Writable for SignalPathReset {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntfConfig0(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum Endian {
+		Little = 0,
+		Big = 1,
+	}
+	impl Endian {
+		pub fn i16(self, bytes: [u8; 2]) -> i16 {
+			match self {
+				Endian::Little => i16::from_le_bytes(bytes),
+				Endian::Big => i16::from_be_bytes(bytes),
+			}
+		}
+
+		pub fn u16(self, bytes: [u8; 2]) -> u16 {
+			match self {
+				Endian::Little => u16::from_le_bytes(bytes),
+				Endian::Big => u16::from_be_bytes(bytes),
+			}
+		}
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum UiSifsCfg {
+		Reserved00 = 0b00,
+		Reserved01 = 0b01,
+		DisableSpi = 0b10,
+		DisableI2c = 0b11,
+	}
+	impl IntfConfig0 {
+		// bits 3:2 are reserved and must be preserved
+		pub const MASK: u8 = 0b1111_0011;
+
+		pub fn pack(fifo_hold_last_data_en: bool, fifo_count_rec: bool, fifo_count_endian: Endian, sensor_data_endian: Endian, ui_sifs_cfg: UiSifsCfg) -> IntfConfig0 {
+			IntfConfig0(
+				(fifo_hold_last_data_en as u8) << 7 | (fifo_count_rec as u8) << 6 | (fifo_count_endian as u8) << 5
+					| (sensor_data_endian as u8) << 4 | (ui_sifs_cfg as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, Endian, Endian, UiSifsCfg) {
+			(
+				self.0 >> 7 & 0b1 != 0,
+				self.0 >> 6 & 0b1 != 0,
+				Endian::n(self.0 >> 5 & 0b1).unwrap(),
+				Endian::n(self.0 >> 4 & 0b1).unwrap(),
+				UiSifsCfg::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for IntfConfig0 {
+		const ADDR: u8 = 0x4C;
+	}
+	byte_register!(IntfConfig0);
+	impl ByteReadable for IntfConfig0 {}
+	impl Writable for IntfConfig0 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntfConfig1(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum AccelLpClkSel {
+		WakeUpOscillator = 0,
+		RcOscillator = 1,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum ClkSel {
+		Rc = 0b00,
+		PllOrRc = 0b01,  // PLL if available, else RC
+		Reserved = 0b10,
+		Disabled = 0b11,
+	}
+	impl IntfConfig1 {
+		// bits 7:4 are reserved and must be preserved
+		pub const MASK: u8 = 0b1111;
+
+		pub fn pack(accel_lp_clk_sel: AccelLpClkSel, rtc_mode: bool, clksel: ClkSel) -> IntfConfig1 {
+			IntfConfig1((accel_lp_clk_sel as u8) << 3 | (rtc_mode as u8) << 2 | (clksel as u8))
+		}
+
+		pub fn depack(&self) -> (AccelLpClkSel, bool, ClkSel) {
+			(
+				AccelLpClkSel::n(self.0 >> 3 & 0b1).unwrap(),
+				self.0 >> 2 & 0b1 != 0,
+				ClkSel::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for IntfConfig1 {
+		const ADDR: u8 = 0x4D;
+	}
+	byte_register!(IntfConfig1);
+	impl ByteReadable for IntfConfig1 {}
+	impl Writable for IntfConfig1 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct PwrMgmt0(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TempDis {
+		Enable = 0,
+		Disable = 1,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum Idle {
+		RcOffWhenSensorsOff = 0,
+		RcOn = 1,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum GyroMode {
+		Off = 0b00,
+		Standby = 0b01,
+		Reserved = 0b10,
+		LowNoise = 0b11,  // do not issue any register writes for 200us after turning on
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum AccelMode {
+		Off = 0b00,
+		Off01 = 0b01,
+		LowPower = 0b10,
+		LowNoise = 0b11,
+	}
+	impl PwrMgmt0 {
+		pub fn pack(temp_dis: TempDis, idle: Idle, gyro_mode: GyroMode, accel_mode: AccelMode) -> PwrMgmt0 {
+			PwrMgmt0((temp_dis as u8) << 5 | (idle as u8) << 4 | (gyro_mode as u8) << 2 | (accel_mode as u8))
+		}
+
+		pub fn depack(&self) -> (TempDis, Idle, GyroMode, AccelMode) {
+			(
+				TempDis::n(self.0 >> 5 & 0b1).unwrap(),
+				Idle::n(self.0 >> 4 & 0b1).unwrap(),
+				GyroMode::n(self.0 >> 2 & 0b11).unwrap(),
+				AccelMode::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for PwrMgmt0 {
+		const ADDR: u8 = 0x4E;
+	}
+	byte_register!(PwrMgmt0);
+	impl ByteReadable for PwrMgmt0 {}
+	impl Writable for PwrMgmt0 {}
+
+	// ODR encoding shared by GYRO_CONFIG0 and ACCEL_CONFIG0.
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum Odr {
+		Reserved0 = 0b0000,
+		Hz32k = 0b0001,
+		Hz16k = 0b0010,
+		Hz8k = 0b0011,
+		Hz4k = 0b0100,
+		Hz2k = 0b0101,
+		Hz1k = 0b0110,
+		Hz200 = 0b0111,
+		Hz100 = 0b1000,
+		Hz50 = 0b1001,
+		Hz25 = 0b1010,
+		Hz12_5 = 0b1011,
+		Hz6_25 = 0b1100,  // accel low power mode only
+		Hz3_125 = 0b1101,  // accel low power mode only
+		Hz1_5625 = 0b1110,  // accel low power mode only
+		Hz500 = 0b1111,
+	}
+	impl Odr {
+		// Nominal sample period with the internal clock.
+		pub fn period_us(self) -> f32 {
+			match self {
+				Odr::Reserved0 => 0.0,
+				Odr::Hz32k => 31.25,
+				Odr::Hz16k => 62.5,
+				Odr::Hz8k => 125.0,
+				Odr::Hz4k => 250.0,
+				Odr::Hz2k => 500.0,
+				Odr::Hz1k => 1_000.0,
+				Odr::Hz200 => 5_000.0,
+				Odr::Hz100 => 10_000.0,
+				Odr::Hz50 => 20_000.0,
+				Odr::Hz25 => 40_000.0,
+				Odr::Hz12_5 => 80_000.0,
+				Odr::Hz6_25 => 160_000.0,
+				Odr::Hz3_125 => 320_000.0,
+				Odr::Hz1_5625 => 640_000.0,
+				Odr::Hz500 => 2_000.0,
+			}
+		}
+
+		// Rates of the accel low power mode, 1.5625 Hz .. 500 Hz.
+		pub fn low_power(self) -> bool {
+			matches!(self, Odr::Hz500 | Odr::Hz200 | Odr::Hz100 | Odr::Hz50 | Odr::Hz25 | Odr::Hz12_5 | Odr::Hz6_25 | Odr::Hz3_125 | Odr::Hz1_5625)
+		}
+	}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct GyroConfig0(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum GyroFs {
+		Dps2000 = 0b000,
+		Dps1000 = 0b001,
+		Dps500 = 0b010,
+		Dps250 = 0b011,
+		Dps125 = 0b100,
+		Dps62_5 = 0b101,
+		Dps31_25 = 0b110,
+		Dps15_625 = 0b111,
+	}
+	impl GyroFs {
+		pub fn lsb_per_dps(self) -> f32 {
+			32768.0 / self.full_scale_dps()
+		}
+
+		pub fn full_scale_dps(self) -> f32 {
+			2000.0 / (1u32 << self as u8) as f32
+		}
+	}
+	impl GyroConfig0 {
+		pub fn pack(gyro_fs: GyroFs, gyro_odr: Odr) -> GyroConfig0 {
+			GyroConfig0((gyro_fs as u8) << 5 | (gyro_odr as u8))
+		}
+
+		pub fn depack(&self) -> (GyroFs, Odr) {
+			(
+				GyroFs::n(self.0 >> 5 & 0b111).unwrap(),
+				Odr::n(self.0 & 0b1111).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for GyroConfig0 {
+		const ADDR: u8 = 0x4F;
+	}
+	byte_register!(GyroConfig0);
+	impl ByteReadable for GyroConfig0 {}
+	impl Writable for GyroConfig0 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelConfig0(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum AccelFs {
+		G16 = 0b000,
+		G8 = 0b001,
+		G4 = 0b010,
+		G2 = 0b011,
+	}
+	impl AccelFs {
+		pub fn lsb_per_g(self) -> f32 {
+			32768.0 / self.full_scale_g()
+		}
+
+		pub fn full_scale_g(self) -> f32 {
+			16.0 / (1u32 << self as u8) as f32
+		}
+	}
+	impl AccelConfig0 {
+		pub fn pack(accel_fs: AccelFs, accel_odr: Odr) -> AccelConfig0 {
+			AccelConfig0((accel_fs as u8) << 5 | (accel_odr as u8))
+		}
+
+		pub fn depack(&self) -> (AccelFs, Odr) {
+			(
+				AccelFs::n(self.0 >> 5 & 0b111).unwrap(),
+				Odr::n(self.0 & 0b1111).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for AccelConfig0 {
+		const ADDR: u8 = 0x50;
+	}
+	byte_register!(AccelConfig0);
+	impl ByteReadable for AccelConfig0 {}
+	impl Writable for AccelConfig0 {}
+
+	// UI filter bandwidth selectors, see the datasheet tables for the bandwidth of each index.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct GyroAccelConfig0(pub u8);
+	impl GyroAccelConfig0 {
+		pub fn pack(accel_ui_filt_bw: u8, gyro_ui_filt_bw: u8) -> GyroAccelConfig0 {
+			GyroAccelConfig0((accel_ui_filt_bw & 0b1111) << 4 | (gyro_ui_filt_bw & 0b1111))
+		}
+
+		pub fn depack(&self) -> (u8, u8) {
+			(self.0 >> 4 & 0b1111, self.0 & 0b1111)
+		}
+	}
+	impl Address<0> for GyroAccelConfig0 {
+		const ADDR: u8 = 0x52;
+	}
+	byte_register!(GyroAccelConfig0);
+	impl ByteReadable for GyroAccelConfig0 {}
+	impl Writable for GyroAccelConfig0 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelConfig1(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum FiltOrd {
+		First = 0b00,
+		Second = 0b01,
+		Third = 0b10,
+		Reserved = 0b11,
+	}
+	impl AccelConfig1 {
+		// bits 7:5 and 0 are reserved and must be preserved
+		pub const MASK: u8 = 0b1_1110;
+
+		pub fn pack(accel_ui_filt_ord: FiltOrd, accel_dec2_m2_ord: FiltOrd) -> AccelConfig1 {
+			AccelConfig1((accel_ui_filt_ord as u8) << 3 | (accel_dec2_m2_ord as u8) << 1)
+		}
+
+		pub fn depack(&self) -> (FiltOrd, FiltOrd) {
+			(
+				FiltOrd::n(self.0 >> 3 & 0b11).unwrap(),
+				FiltOrd::n(self.0 >> 1 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for AccelConfig1 {
+		const ADDR: u8 = 0x53;
+	}
+	byte_register!(AccelConfig1);
+	impl ByteReadable for AccelConfig1 {}
+	impl Writable for AccelConfig1 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TmstConfig(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TmstRes {
+		Us1 = 0,
+		Us16 = 1,  // 16us, or one RTC period when RTC mode is enabled, see `timestamp::TickPeriod`
+	}
+	impl TmstConfig {
+		// bits 7:5 are reserved and must be preserved
+		pub const MASK: u8 = 0b1_1111;
+
+		pub fn pack(to_regs_en: bool, res: TmstRes, delta_en: bool, fsync_en: bool, en: bool) -> TmstConfig {
+			TmstConfig((to_regs_en as u8) << 4 | (res as u8) << 3 | (delta_en as u8) << 2 | (fsync_en as u8) << 1 | (en as u8))
+		}
+
+		pub fn depack(&self) -> (bool, TmstRes, bool, bool, bool) {
+			(
+				self.0 >> 4 & 0b1 != 0,
+				TmstRes::n(self.0 >> 3 & 0b1).unwrap(),
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<0> for TmstConfig {
+		const ADDR: u8 = 0x54;
+	}
+	byte_register!(TmstConfig);
+	impl ByteReadable for TmstConfig {}
+	impl Writable for TmstConfig {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig0(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum DmpOdr {
+		Hz25 = 0b00,
+		Reserved01 = 0b01,
+		Hz50 = 0b10,
+		Reserved11 = 0b11,
+	}
+	impl DmpOdr {
+		// The accel has to run at this rate for the DMP.
+		pub fn accel_odr(self) -> Odr {
+			match self {
+				DmpOdr::Hz25 => Odr::Hz25,
+				_ => Odr::Hz50,
+			}
+		}
+
+		pub fn hz(self) -> f32 {
+			match self {
+				DmpOdr::Hz25 => 25.0,
+				_ => 50.0,
+			}
+		}
+	}
+	impl ApexConfig0 {
+		pub fn pack(dmp_power_save: bool, tap_enable: bool, ped_enable: bool, tilt_enable: bool, r2w_en: bool, dmp_odr: DmpOdr) -> ApexConfig0 {
+			ApexConfig0(
+				(dmp_power_save as u8) << 7 | (tap_enable as u8) << 6 | (ped_enable as u8) << 5 | (tilt_enable as u8) << 4
+					| (r2w_en as u8) << 3 | (dmp_odr as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, DmpOdr) {
+			(
+				self.0 >> 7 & 0b1 != 0,
+				self.0 >> 6 & 0b1 != 0,
+				self.0 >> 5 & 0b1 != 0,
+				self.0 >> 4 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				DmpOdr::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for ApexConfig0 {
+		const ADDR: u8 = 0x56;
+	}
+	byte_register!(ApexConfig0);
+	impl ByteReadable for ApexConfig0 {}
+	impl Writable for ApexConfig0 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct SmdConfig(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum WomIntMode {
+		Or = 0,  // any enabled axis exceeds its threshold
+		And = 1,  // all enabled axes exceed their thresholds
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum WomMode {
+		CompareInitial = 0,
+		ComparePrevious = 1,
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum SmdMode {
+		Disabled = 0b00,
+		Wom = 0b01,
+		Short = 0b10,  // SMD, 1 s wait between two WOM events
+		Long = 0b11,  // SMD, 3 s wait between two WOM events
+	}
+	impl SmdConfig {
+		pub fn pack(wom_int_mode: WomIntMode, wom_mode: WomMode, smd_mode: SmdMode) -> SmdConfig {
+			SmdConfig((wom_int_mode as u8) << 3 | (wom_mode as u8) << 2 | (smd_mode as u8))
+		}
+
+		pub fn depack(&self) -> (WomIntMode, WomMode, SmdMode) {
+			(
+				WomIntMode::n(self.0 >> 3 & 0b1).unwrap(),
+				WomMode::n(self.0 >> 2 & 0b1).unwrap(),
+				SmdMode::n(self.0 & 0b11).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for SmdConfig {
+		const ADDR: u8 = 0x57;
+	}
+	byte_register!(SmdConfig);
+	impl ByteReadable for SmdConfig {}
+	impl Writable for SmdConfig {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoConfig1(pub u8);
+	impl FifoConfig1 {
+		pub fn pack(resume_partial_rd: bool, wm_gt_th: bool, hires_en: bool, tmst_fsync_en: bool, temp_en: bool, gyro_en: bool, accel_en: bool) -> FifoConfig1 {
+			FifoConfig1(
+				(resume_partial_rd as u8) << 6 | (wm_gt_th as u8) << 5 | (hires_en as u8) << 4 | (tmst_fsync_en as u8) << 3
+					| (temp_en as u8) << 2 | (gyro_en as u8) << 1 | (accel_en as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 6 & 0b1 != 0,
+				self.0 >> 5 & 0b1 != 0,
+				self.0 >> 4 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<0> for FifoConfig1 {
+		const ADDR: u8 = 0x5F;
+	}
+	byte_register!(FifoConfig1);
+	impl ByteReadable for FifoConfig1 {}
+	impl Writable for FifoConfig1 {}
+
+	// FIFO watermark, 12 bits split over FIFO_CONFIG2 (7:0) and FIFO_CONFIG3 (11:8).
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoConfig2(pub u8);
+	impl Address<0> for FifoConfig2 {
+		const ADDR: u8 = 0x60;
+	}
+	byte_register!(FifoConfig2);
+	impl ByteReadable for FifoConfig2 {}
+	impl Writable for FifoConfig2 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FifoConfig3(pub u8);
+	impl Address<0> for FifoConfig3 {
+		const ADDR: u8 = 0x61;
+	}
+	byte_register!(FifoConfig3);
+	impl ByteReadable for FifoConfig3 {}
+	impl Writable for FifoConfig3 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct FsyncConfig(pub u8);
+	// Data register whose LSB is replaced by the FSYNC flag.
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum FsyncUiSel {
+		None = 0,
+		TempOut = 1,
+		GyroXOut = 2,
+		GyroYOut = 3,
+		GyroZOut = 4,
+		AccelXOut = 5,
+		AccelYOut = 6,
+		AccelZOut = 7,
+	}
+	impl FsyncUiSel {
+		// Address of the high byte of the tagged register.
+		pub fn data_addr(self) -> Option<u8> {
+			match self {
+				FsyncUiSel::None => None,
+				FsyncUiSel::TempOut => Some(0x1D),
+				FsyncUiSel::AccelXOut => Some(0x1F),
+				FsyncUiSel::AccelYOut => Some(0x21),
+				FsyncUiSel::AccelZOut => Some(0x23),
+				FsyncUiSel::GyroXOut => Some(0x25),
+				FsyncUiSel::GyroYOut => Some(0x27),
+				FsyncUiSel::GyroZOut => Some(0x29),
+			}
+		}
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum FsyncUiFlagClearSel {
+		OnUpdate = 0,  // cleared when the tagged register is updated
+		OnRead = 1,  // cleared when the tagged register is read
+	}
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum FsyncPolarity {
+		RisingEdge = 0,
+		FallingEdge = 1,
+	}
+	impl FsyncConfig {
+		pub fn pack(ui_sel: FsyncUiSel, ui_flag_clear_sel: FsyncUiFlagClearSel, polarity: FsyncPolarity) -> FsyncConfig {
+			FsyncConfig((ui_sel as u8) << 4 | (ui_flag_clear_sel as u8) << 1 | (polarity as u8))
+		}
+
+		pub fn depack(&self) -> (FsyncUiSel, FsyncUiFlagClearSel, FsyncPolarity) {
+			(
+				FsyncUiSel::n(self.0 >> 4 & 0b111).unwrap(),
+				FsyncUiFlagClearSel::n(self.0 >> 1 & 0b1).unwrap(),
+				FsyncPolarity::n(self.0 & 0b1).unwrap(),
+			)
+		}
+	}
+	impl Address<0> for FsyncConfig {
+		const ADDR: u8 = 0x62;
+	}
+	byte_register!(FsyncConfig);
+	impl ByteReadable for FsyncConfig {}
+	impl Writable for FsyncConfig {}
+
+	// INT_SOURCE1 routes to INT1, INT_SOURCE4 has the same layout for INT2.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntSource1(pub u8);
+	impl IntSource1 {
+		pub fn pack(i3c_protocol_error_en: bool, smd_en: bool, wom_z_en: bool, wom_y_en: bool, wom_x_en: bool) -> IntSource1 {
+			IntSource1((i3c_protocol_error_en as u8) << 6 | (smd_en as u8) << 3 | (wom_z_en as u8) << 2 | (wom_y_en as u8) << 1 | (wom_x_en as u8))
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 6 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<0> for IntSource1 {
+		const ADDR: u8 = 0x66;
+	}
+	byte_register!(IntSource1);
+	impl ByteReadable for IntSource1 {}
+	impl Writable for IntSource1 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntSource4(pub u8);
+	impl IntSource4 {
+		pub fn pack(i3c_protocol_error_en: bool, smd_en: bool, wom_z_en: bool, wom_y_en: bool, wom_x_en: bool) -> IntSource4 {
+			IntSource4(IntSource1::pack(i3c_protocol_error_en, smd_en, wom_z_en, wom_y_en, wom_x_en).0)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool) {
+			IntSource1(self.0).depack()
+		}
+	}
+	impl Address<0> for IntSource4 {
+		const ADDR: u8 = 0x69;
+	}
+	byte_register!(IntSource4);
+	impl ByteReadable for IntSource4 {}
+	impl Writable for IntSource4 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct WhoAmI(pub u8);
+	impl WhoAmI {
+		pub const ICM42688: u8 = 0x47;
+	}
+	impl Address<0> for WhoAmI {
+		const ADDR: u8 = 0x75;
+	}
+	byte_register!(WhoAmI);
+	impl ByteReadable for WhoAmI {}
+}
+
+pub mod bank1 {
+	use super::*;
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct SensorConfig0(pub u8);
+	impl SensorConfig0 {
+		// bits 7:6 are reserved and must be preserved
+		pub const MASK: u8 = 0b11_1111;
+
+		pub fn pack(zg_disable: bool, yg_disable: bool, xg_disable: bool, za_disable: bool, ya_disable: bool, xa_disable: bool) -> SensorConfig0 {
+			SensorConfig0(
+				(zg_disable as u8) << 5 | (yg_disable as u8) << 4 | (xg_disable as u8) << 3
+					| (za_disable as u8) << 2 | (ya_disable as u8) << 1 | (xa_disable as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 5 & 0b1 != 0,
+				self.0 >> 4 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<1> for SensorConfig0 {
+		const ADDR: u8 = 0x03;
+	}
+	byte_register!(SensorConfig0);
+	impl ByteReadable for SensorConfig0 {}
+	impl Writable for SensorConfig0 {}
+
+	// TMSTVAL0..2 hold the 20 bit timestamp latched by SIGNAL_PATH_RESET.TMST_STROBE (needs TMST_CONFIG.TMST_TO_REGS_EN).
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TmstVal0(pub u8);
+	impl Address<1> for TmstVal0 {
+		const ADDR: u8 = 0x62;
+	}
+	byte_register!(TmstVal0);
+	impl ByteReadable for TmstVal0 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TmstVal1(pub u8);
+	impl Address<1> for TmstVal1 {
+		const ADDR: u8 = 0x63;
+	}
+	byte_register!(TmstVal1);
+	impl ByteReadable for TmstVal1 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct TmstVal2(pub u8);
+	impl Address<1> for TmstVal2 {
+		const ADDR: u8 = 0x64;
+	}
+	byte_register!(TmstVal2);
+	impl ByteReadable for TmstVal2 {}
+
+	pub fn depack_tmstval(val0: TmstVal0, val1: TmstVal1, val2: TmstVal2) -> u32 {
+		((val2.0 & 0x0F) as u32) << 16 | (val1.0 as u32) << 8 | val0.0 as u32
+	}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntfConfig4(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum SpiWire {
+		ThreeWire = 0,
+		FourWire = 1,
+	}
+	impl IntfConfig4 {
+		// bits 7, 5:2 and 0 are reserved and must be preserved, bit 6 is I3C_BUS_MODE
+		pub const MASK: u8 = 0b10;
+
+		pub fn pack(spi_ap_4wire: SpiWire) -> IntfConfig4 {
+			IntfConfig4((spi_ap_4wire as u8) << 1)
+		}
+
+		pub fn depack(&self) -> SpiWire {
+			SpiWire::n(self.0 >> 1 & 0b1).unwrap()
+		}
+	}
+	impl Address<1> for IntfConfig4 {
+		const ADDR: u8 = 0x7A;
+	}
+	byte_register!(IntfConfig4);
+	impl ByteReadable for IntfConfig4 {}
+	impl Writable for IntfConfig4 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntfConfig5(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum Pin9Function {
+		Int2 = 0b00,
+		Fsync = 0b01,
+		Clkin = 0b10,
+		Reserved = 0b11,
+	}
+	impl IntfConfig5 {
+		// other bits are reserved and must be preserved
+		pub const MASK: u8 = 0b110;
+
+		pub fn pack(pin9_function: Pin9Function) -> IntfConfig5 {
+			IntfConfig5((pin9_function as u8) << 1)
+		}
+
+		pub fn depack(&self) -> Pin9Function {
+			Pin9Function::n(self.0 >> 1 & 0b11).unwrap()
+		}
+	}
+	impl Address<1> for IntfConfig5 {
+		const ADDR: u8 = 0x7B;
+	}
+	byte_register!(IntfConfig5);
+	impl ByteReadable for IntfConfig5 {}
+	impl Writable for IntfConfig5 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntfConfig6(pub u8);
+	impl IntfConfig6 {
+		// bits 7:5 are reserved and must be preserved
+		pub const MASK: u8 = 0b1_1111;
+
+		pub fn pack(i3c_en: bool, i3c_ibi_byte_en: bool, i3c_ibi_en: bool, i3c_ddr_en: bool, i3c_sdr_en: bool) -> IntfConfig6 {
+			IntfConfig6(
+				(i3c_en as u8) << 4 | (i3c_ibi_byte_en as u8) << 3 | (i3c_ibi_en as u8) << 2
+					| (i3c_ddr_en as u8) << 1 | (i3c_sdr_en as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 4 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<1> for IntfConfig6 {
+		const ADDR: u8 = 0x7C;
+	}
+	byte_register!(IntfConfig6);
+	impl ByteReadable for IntfConfig6 {}
+	impl Writable for IntfConfig6 {}
+}
+
+pub mod bank4 {
+	use super::*;
+
+	// APEX_CONFIG1..3 take selector indices into the threshold tables of the datasheet.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig1(pub u8);
+	impl ApexConfig1 {
+		pub fn pack(low_energy_amp_th_sel: u8, dmp_power_save_time_sel: u8) -> ApexConfig1 {
+			ApexConfig1((low_energy_amp_th_sel & 0b1111) << 4 | (dmp_power_save_time_sel & 0b1111))
+		}
+
+		pub fn depack(&self) -> (u8, u8) {
+			(self.0 >> 4 & 0b1111, self.0 & 0b1111)
+		}
+	}
+	impl Address<4> for ApexConfig1 {
+		const ADDR: u8 = 0x40;
+	}
+	byte_register!(ApexConfig1);
+	impl ByteReadable for ApexConfig1 {}
+	impl Writable for ApexConfig1 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig2(pub u8);
+	impl ApexConfig2 {
+		pub fn pack(ped_amp_th_sel: u8, ped_step_cnt_th_sel: u8) -> ApexConfig2 {
+			ApexConfig2((ped_amp_th_sel & 0b1111) << 4 | (ped_step_cnt_th_sel & 0b1111))
+		}
+
+		pub fn depack(&self) -> (u8, u8) {
+			(self.0 >> 4 & 0b1111, self.0 & 0b1111)
+		}
+	}
+	impl Address<4> for ApexConfig2 {
+		const ADDR: u8 = 0x41;
+	}
+	byte_register!(ApexConfig2);
+	impl ByteReadable for ApexConfig2 {}
+	impl Writable for ApexConfig2 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig3(pub u8);
+	impl ApexConfig3 {
+		pub fn pack(ped_step_det_th_sel: u8, ped_sb_timer_th_sel: u8, ped_hi_en_th_sel: u8) -> ApexConfig3 {
+			ApexConfig3((ped_step_det_th_sel & 0b111) << 5 | (ped_sb_timer_th_sel & 0b111) << 2 | (ped_hi_en_th_sel & 0b11))
+		}
+
+		pub fn depack(&self) -> (u8, u8, u8) {
+			(self.0 >> 5 & 0b111, self.0 >> 2 & 0b111, self.0 & 0b11)
+		}
+	}
+	impl Address<4> for ApexConfig3 {
+		const ADDR: u8 = 0x42;
+	}
+	byte_register!(ApexConfig3);
+	impl ByteReadable for ApexConfig3 {}
+	impl Writable for ApexConfig3 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig4(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum TiltWaitTime {
+		S0 = 0b00,
+		S2 = 0b01,
+		S4 = 0b10,
+		S6 = 0b11,
+	}
+	// Raise-to-sleep timeout, multiples of 1.28 s.
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum SleepTimeOut {
+		S1_28 = 0b000,
+		S2_56 = 0b001,
+		S3_84 = 0b010,
+		S5_12 = 0b011,
+		S6_40 = 0b100,
+		S7_68 = 0b101,
+		S8_96 = 0b110,
+		S10_24 = 0b111,
+	}
+	impl ApexConfig4 {
+		// bits 2:0 are reserved (reset value 0b100) and must be preserved
+		pub const MASK: u8 = 0b1111_1000;
+
+		pub fn pack(tilt_wait_time_sel: TiltWaitTime, sleep_time_out: SleepTimeOut) -> ApexConfig4 {
+			ApexConfig4((tilt_wait_time_sel as u8) << 6 | (sleep_time_out as u8) << 3)
+		}
+
+		pub fn depack(&self) -> (TiltWaitTime, SleepTimeOut) {
+			(
+				TiltWaitTime::n(self.0 >> 6 & 0b11).unwrap(),
+				SleepTimeOut::n(self.0 >> 3 & 0b111).unwrap(),
+			)
+		}
+	}
+	impl Address<4> for ApexConfig4 {
+		const ADDR: u8 = 0x43;
+	}
+	byte_register!(ApexConfig4);
+	impl ByteReadable for ApexConfig4 {}
+	impl Writable for ApexConfig4 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig5(pub u8);
+	// How the device is mounted, as the matrix taking sensor axes to device axes.
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum MountingOrientation {
+		Identity = 0b000,
+		RotX180 = 0b001,
+		RotY180 = 0b010,
+		RotZ180 = 0b011,
+		SwapXyFlipZ = 0b100,  // 180 degrees around the x = y diagonal
+		RotZ270 = 0b101,
+		RotZ90 = 0b110,
+		SwapNegXyFlipZ = 0b111,  // 180 degrees around the x = -y diagonal
+	}
+	impl MountingOrientation {
+		pub const ALL: [MountingOrientation; 8] = [
+			MountingOrientation::Identity,
+			MountingOrientation::RotX180,
+			MountingOrientation::RotY180,
+			MountingOrientation::RotZ180,
+			MountingOrientation::SwapXyFlipZ,
+			MountingOrientation::RotZ270,
+			MountingOrientation::RotZ90,
+			MountingOrientation::SwapNegXyFlipZ,
+		];
+
+		pub fn matrix(self) -> [[i8; 3]; 3] {
+			match self {
+				MountingOrientation::Identity => [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+				MountingOrientation::RotX180 => [[1, 0, 0], [0, -1, 0], [0, 0, -1]],
+				MountingOrientation::RotY180 => [[-1, 0, 0], [0, 1, 0], [0, 0, -1]],
+				MountingOrientation::RotZ180 => [[-1, 0, 0], [0, -1, 0], [0, 0, 1]],
+				MountingOrientation::SwapXyFlipZ => [[0, 1, 0], [1, 0, 0], [0, 0, -1]],
+				MountingOrientation::RotZ270 => [[0, 1, 0], [-1, 0, 0], [0, 0, 1]],
+				MountingOrientation::RotZ90 => [[0, -1, 0], [1, 0, 0], [0, 0, 1]],
+				MountingOrientation::SwapNegXyFlipZ => [[0, -1, 0], [-1, 0, 0], [0, 0, -1]],
+			}
+		}
+	}
+	impl ApexConfig5 {
+		pub fn pack(mounting_matrix: MountingOrientation) -> ApexConfig5 {
+			ApexConfig5(mounting_matrix as u8)
+		}
+
+		pub fn depack(&self) -> MountingOrientation {
+			MountingOrientation::n(self.0 & 0b111).unwrap()
+		}
+	}
+	impl Address<4> for ApexConfig5 {
+		const ADDR: u8 = 0x44;
+	}
+	byte_register!(ApexConfig5);
+	impl ByteReadable for ApexConfig5 {}
+	impl Writable for ApexConfig5 {}
+
+	// Raise-to-sleep gesture delay, (SLEEP_GESTURE_DELAY + 1) * 0.32 s.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig6(pub u8);
+	impl ApexConfig6 {
+		// bits 7:3 are reserved and must be preserved
+		pub const MASK: u8 = 0b111;
+
+		pub fn pack(sleep_gesture_delay: u8) -> ApexConfig6 {
+			ApexConfig6(sleep_gesture_delay & 0b111)
+		}
+
+		pub fn depack(&self) -> u8 {
+			self.0 & 0b111
+		}
+	}
+	impl Address<4> for ApexConfig6 {
+		const ADDR: u8 = 0x45;
+	}
+	byte_register!(ApexConfig6);
+	impl ByteReadable for ApexConfig6 {}
+	impl Writable for ApexConfig6 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig7(pub u8);
+	impl ApexConfig7 {
+		pub fn pack(tap_min_jerk_thr: u8, tap_max_peak_tol: u8) -> ApexConfig7 {
+			ApexConfig7((tap_min_jerk_thr & 0b11_1111) << 2 | (tap_max_peak_tol & 0b11))
+		}
+
+		pub fn depack(&self) -> (u8, u8) {
+			(self.0 >> 2 & 0b11_1111, self.0 & 0b11)
+		}
+	}
+	impl Address<4> for ApexConfig7 {
+		const ADDR: u8 = 0x46;
+	}
+	byte_register!(ApexConfig7);
+	impl ByteReadable for ApexConfig7 {}
+	impl Writable for ApexConfig7 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig8(pub u8);
+	impl ApexConfig8 {
+		pub fn pack(tap_tmax: u8, tap_tmin: u8, tap_smudge_reject_thr: u8) -> ApexConfig8 {
+			ApexConfig8((tap_tmax & 0b11) << 5 | (tap_tmin & 0b11) << 3 | (tap_smudge_reject_thr & 0b111))
+		}
+
+		pub fn depack(&self) -> (u8, u8, u8) {
+			(self.0 >> 5 & 0b11, self.0 >> 3 & 0b11, self.0 & 0b111)
+		}
+	}
+	impl Address<4> for ApexConfig8 {
+		const ADDR: u8 = 0x47;
+	}
+	byte_register!(ApexConfig8);
+	impl ByteReadable for ApexConfig8 {}
+	impl Writable for ApexConfig8 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct ApexConfig9(pub u8);
+	#[derive(Clone, Copy, PartialEq, Eq, Debug, enumn::N)]
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	#[repr(u8)]
+	pub enum SensitivityMode {
+		LowPower = 0,  // for a 25 Hz DMP
+		HighPerformance = 1,  // for a 50 Hz DMP
+	}
+	impl ApexConfig9 {
+		// bits 7:1 are reserved and must be preserved
+		pub const MASK: u8 = 0b1;
+
+		pub fn pack(sensitivity_mode: SensitivityMode) -> ApexConfig9 {
+			ApexConfig9(sensitivity_mode as u8)
+		}
+
+		pub fn depack(&self) -> SensitivityMode {
+			SensitivityMode::n(self.0 & 0b1).unwrap()
+		}
+	}
+	impl Address<4> for ApexConfig9 {
+		const ADDR: u8 = 0x48;
+	}
+	byte_register!(ApexConfig9);
+	impl ByteReadable for ApexConfig9 {}
+	impl Writable for ApexConfig9 {}
+
+	// Wake-on-motion thresholds, 1/256 g per LSB.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelWomXThr(pub u8);
+	impl Address<4> for AccelWomXThr {
+		const ADDR: u8 = 0x4A;
+	}
+	byte_register!(AccelWomXThr);
+	impl ByteReadable for AccelWomXThr {}
+	impl Writable for AccelWomXThr {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelWomYThr(pub u8);
+	impl Address<4> for AccelWomYThr {
+		const ADDR: u8 = 0x4B;
+	}
+	byte_register!(AccelWomYThr);
+	impl ByteReadable for AccelWomYThr {}
+	impl Writable for AccelWomYThr {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct AccelWomZThr(pub u8);
+	impl Address<4> for AccelWomZThr {
+		const ADDR: u8 = 0x4C;
+	}
+	byte_register!(AccelWomZThr);
+	impl ByteReadable for AccelWomZThr {}
+	impl Writable for AccelWomZThr {}
+
+	// INT_SOURCE6 routes APEX events to INT1, INT_SOURCE7 has the same layout for INT2.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntSource6(pub u8);
+	impl IntSource6 {
+		pub fn pack(step_det_en: bool, step_cnt_ofl_en: bool, tilt_det_en: bool, wake_det_en: bool, sleep_det_en: bool, tap_det_en: bool) -> IntSource6 {
+			IntSource6(
+				(step_det_en as u8) << 5 | (step_cnt_ofl_en as u8) << 4 | (tilt_det_en as u8) << 3 | (wake_det_en as u8) << 2
+					| (sleep_det_en as u8) << 1 | (tap_det_en as u8)
+			)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, bool) {
+			(
+				self.0 >> 5 & 0b1 != 0,
+				self.0 >> 4 & 0b1 != 0,
+				self.0 >> 3 & 0b1 != 0,
+				self.0 >> 2 & 0b1 != 0,
+				self.0 >> 1 & 0b1 != 0,
+				self.0 & 0b1 != 0,
+			)
+		}
+	}
+	impl Address<4> for IntSource6 {
+		const ADDR: u8 = 0x4D;
+	}
+	byte_register!(IntSource6);
+	impl ByteReadable for IntSource6 {}
+	impl Writable for IntSource6 {}
+
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct IntSource7(pub u8);
+	impl IntSource7 {
+		pub fn pack(step_det_en: bool, step_cnt_ofl_en: bool, tilt_det_en: bool, wake_det_en: bool, sleep_det_en: bool, tap_det_en: bool) -> IntSource7 {
+			IntSource7(IntSource6::pack(step_det_en, step_cnt_ofl_en, tilt_det_en, wake_det_en, sleep_det_en, tap_det_en).0)
+		}
+
+		pub fn depack(&self) -> (bool, bool, bool, bool, bool, bool) {
+			IntSource6(self.0).depack()
+		}
+	}
+	impl Address<4> for IntSource7 {
+		const ADDR: u8 = 0x4E;
+	}
+	byte_register!(IntSource7);
+	impl ByteReadable for IntSource7 {}
+	impl Writable for IntSource7 {}
+
+	// OFFSET_USER0 .. OFFSET_USER8 (0x77..=0x7F).
+	// Gyro offsets are 12 bit two's complement at 1/32 dps, accel offsets at 1/2 mg.
+	#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+	pub struct OffsetUser<const I: u8>(pub u8);
+	impl<const I: u8> Address<4> for OffsetUser<I> {
+		const ADDR: u8 = 0x77 + I;
+	}
+	impl<const I: u8> From<u8> for OffsetUser<I> {
+		fn from(value: u8) -> Self {
+			Self(value)
+		}
+	}
+	impl<const I: u8> From<OffsetUser<I>> for u8 {
+		fn from(value: OffsetUser<I>) -> Self {
+			value.0
+		}
+	}
+	impl<const I: u8> ByteReadable for OffsetUser<I> {}
+	impl<const I: u8> Writable for OffsetUser<I> {}
+
+	pub fn pack_offset_user(gyro: [i16; 3], accel: [i16; 3]) -> [u8; 9] {
+		let g = gyro.map(|v| v.clamp(-2048, 2047) as u16 & 0x0FFF);
+		let a = accel.map(|v| v.clamp(-2048, 2047) as u16 & 0x0FFF);
+		[
+			g[0] as u8,
+			((g[1] >> 8) as u8) << 4 | (g[0] >> 8) as u8,
+			g[1] as u8,
+			g[2] as u8,
+			((a[0] >> 8) as u8) << 4 | (g[2] >> 8) as u8,
+			a[0] as u8,
+			a[1] as u8,
+			((a[2] >> 8) as u8) << 4 | (a[1] >> 8) as u8,
+			a[2] as u8,
+		]
+	}
+
+	pub fn depack_offset_user(bytes: [u8; 9]) -> ([i16; 3], [i16; 3]) {
+		// sign-extend a 12 bit value
+		let s = |high: u8, low: u8| ((((high & 0x0F) as u16) << 8 | low as u16) << 4) as i16 >> 4;
+		(
+			[s(bytes[1], bytes[0]), s(bytes[1] >> 4, bytes[2]), s(bytes[4], bytes[3])],
+			[s(bytes[4] >> 4, bytes[5]), s(bytes[7], bytes[6]), s(bytes[7] >> 4, bytes[8])],
+		)
+	}
+}
@@ -1,4 +1,4 @@
-trait ByteReadable {}  // R
-trait WordReadable {}  // SYNCR
-trait MultiReadable {}  // Only FIFO
-trait Writable {}  // W
+pub trait ByteReadable: From<u8> {}  // R
+pub trait WordReadable: From<[u8; 2]> {}  // SYNCR
+pub trait MultiReadable {}  // Only FIFO
+pub trait Writable: Into<u8> {}  // W
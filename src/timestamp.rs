@@ -0,0 +1,171 @@
+use crate::clock::ClockInput;
+use crate::register::bank0::{FsyncPolarity, FsyncUiFlagClearSel, FsyncUiSel, TmstRes};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TimestampConfig {
+	pub enable: bool,
+	pub resolution: TmstRes,
+	pub delta: bool,  // FIFO timestamps are the time since the previous ODR event
+	pub fsync_capture: bool,  // latch the time of the last FSYNC edge into TMST_FSYNC / the FIFO
+	pub to_registers: bool,  // allow TMSTVAL to be read after a TMST_STROBE
+}
+
+impl Default for TimestampConfig {
+	// power-on reset value of TMST_CONFIG
+	fn default() -> Self {
+		Self {
+			enable: true,
+			resolution: TmstRes::Us1,
+			delta: false,
+			fsync_capture: true,
+			to_registers: false,
+		}
+	}
+}
+
+// FSYNC input on pin 9. The edge is flagged in the LSB of `ui_sel` and timed relative to the next ODR event.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct FsyncSettings {
+	pub ui_sel: FsyncUiSel,
+	pub flag_clear: FsyncUiFlagClearSel,
+	pub polarity: FsyncPolarity,
+}
+
+// Length of one timestamp tick. TMST_RES selects 1 us or 16 us, but in RTC mode the coarse setting counts CLKIN
+// periods instead (about 30.5 us at 32.768 kHz), so the period is kept as a fraction of nanoseconds.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TickPeriod {
+	ns: u64,
+	per: u64,
+}
+
+impl TickPeriod {
+	pub fn new(resolution: TmstRes, clock: ClockInput) -> Self {
+		match (resolution, clock) {
+			(TmstRes::Us1, _) => Self { ns: 1000, per: 1 },
+			(TmstRes::Us16, ClockInput::Internal) => Self { ns: 16_000, per: 1 },
+			(TmstRes::Us16, ClockInput::Clkin(hz)) => Self { ns: 1_000_000_000, per: hz.max(1) as u64 },
+		}
+	}
+
+	pub fn ticks_to_ns(self, ticks: u64) -> u64 {
+		(ticks as u128 * self.ns as u128 / self.per as u128) as u64
+	}
+
+	pub fn ticks_to_us(self, ticks: u64) -> u64 {
+		self.ticks_to_ns(ticks) / 1000
+	}
+}
+
+// Unwraps a rolling hardware counter (20 bit TMSTVAL or 16 bit FIFO timestamp) into a monotonically increasing microsecond count.
+// Consecutive updates must be less than one rollover period apart.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Timestamp {
+	bits: u32,
+	tick: TickPeriod,
+	last_raw: Option<u32>,
+	ticks: u64,
+}
+
+impl Timestamp {
+	pub const TMSTVAL_BITS: u32 = 20;
+	pub const FIFO_BITS: u32 = 16;
+
+	pub fn new(bits: u32, tick: TickPeriod) -> Self {
+		Self { bits, tick, last_raw: None, ticks: 0 }
+	}
+
+	pub fn tmstval(tick: TickPeriod) -> Self {
+		Self::new(Self::TMSTVAL_BITS, tick)
+	}
+
+	pub fn fifo(tick: TickPeriod) -> Self {
+		Self::new(Self::FIFO_BITS, tick)
+	}
+
+	fn mask(&self) -> u32 {
+		(1 << self.bits) - 1
+	}
+
+	// Feeds an absolute counter value and returns the unwrapped time in microseconds.
+	pub fn update(&mut self, raw: u32) -> u64 {
+		let raw = raw & self.mask();
+		self.ticks += match self.last_raw {
+			Some(last) => (raw.wrapping_sub(last) & self.mask()) as u64,
+			None => raw as u64,
+		};
+		self.last_raw = Some(raw);
+		self.now_us()
+	}
+
+	// Feeds a counter delta (TMST_DELTA_EN) and returns the unwrapped time in microseconds.
+	pub fn advance(&mut self, delta: u32) -> u64 {
+		let delta = delta & self.mask();
+		self.ticks += delta as u64;
+		self.last_raw = Some(self.last_raw.unwrap_or(0).wrapping_add(delta) & self.mask());
+		self.now_us()
+	}
+
+	pub fn now_us(&self) -> u64 {
+		self.tick.ticks_to_us(self.ticks)
+	}
+
+	pub fn ticks(&self) -> u64 {
+		self.ticks
+	}
+
+	// Longest allowed gap between updates.
+	pub fn rollover_us(&self) -> u64 {
+		self.tick.ticks_to_us(1 << self.bits)
+	}
+
+	pub fn reset(&mut self) {
+		self.last_raw = None;
+		self.ticks = 0;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const US1: TickPeriod = TickPeriod { ns: 1000, per: 1 };
+
+	#[test]
+	fn tmstval_unwraps_across_rollover() {
+		let mut timestamp = Timestamp::tmstval(US1);
+		assert_eq!(timestamp.rollover_us(), 1 << 20);
+		assert_eq!(timestamp.update(0xF_FF00), 0xF_FF00);
+		// bits above the counter width are ignored
+		assert_eq!(timestamp.update(0xF0_0100), 0x10_0100);
+		assert_eq!(timestamp.update(0x8_0000), 0x18_0000);
+		assert_eq!(timestamp.update(0x8_0000), 0x18_0000);
+		timestamp.reset();
+		assert_eq!(timestamp.update(5), 5);
+	}
+
+	#[test]
+	fn fifo_deltas_accumulate_across_rollover() {
+		let mut timestamp = Timestamp::fifo(US1);
+		assert_eq!(timestamp.advance(0xFFF0), 0xFFF0);
+		assert_eq!(timestamp.advance(0x20), 0x1_0010);
+		// an absolute update continues from the counter value the deltas reached
+		assert_eq!(timestamp.update(0x30), 0x1_0030);
+	}
+
+	#[test]
+	fn tick_periods() {
+		let us16 = TickPeriod::new(TmstRes::Us16, ClockInput::Internal);
+		assert_eq!(us16.ticks_to_us(3), 48);
+		assert_eq!(Timestamp::fifo(us16).rollover_us(), 16 << 16);
+		assert_eq!(TickPeriod::new(TmstRes::Us1, ClockInput::Clkin(32_768)), US1);
+		// RTC mode counts CLKIN periods
+		let rtc = TickPeriod::new(TmstRes::Us16, ClockInput::Clkin(32_768));
+		assert_eq!(rtc.ticks_to_ns(1), 30_517);
+		assert_eq!(rtc.ticks_to_us(32_768), 1_000_000);
+	}
+}
@@ -0,0 +1,176 @@
+// Byte level access to the register file. Bank selection is handled above this layer by `Constrainer`.
+#[allow(async_fn_in_trait)]
+pub trait Transport {
+	type Error;
+
+	// Burst read starting at `addr` in one bus transaction. The device auto-increments the address.
+	async fn read(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error>;
+	async fn write(&mut self, addr: u8, value: u8) -> Result<(), Self::Error>;
+
+	// Like `read`, but `buf[0]` is the address slot and the data lands in `buf[1..]`, so a
+	// transport can do the whole access as one full duplex transfer over a single (DMA) buffer.
+	async fn read_in_place(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+		match buf.split_first_mut() {
+			Some((_, data)) => self.read(addr, data).await,
+			None => Ok(()),
+		}
+	}
+}
+
+// Transports that talk to the device over its SPI interface.
+pub trait SpiInterface: Transport {}
+
+pub use blocking_spi::BlockingSpiTransport;
+pub use i3c::{I3cBus, I3cTransport};
+#[cfg(feature = "async")]
+pub use spi::SpiTransport;
+
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
+
+#[cfg(feature = "async")]
+mod spi {
+	use embedded_hal_async::spi::{Operation, SpiDevice};
+
+	use super::{SpiInterface, Transport};
+
+	const READ: u8 = 0x80;
+
+	pub struct SpiTransport<D> {
+		device: D,
+	}
+
+	impl<D: SpiDevice> SpiTransport<D> {
+		pub fn new(device: D) -> Self {
+			Self { device }
+		}
+
+		pub fn release(self) -> D {
+			self.device
+		}
+	}
+
+	impl<D: SpiDevice> Transport for SpiTransport<D> {
+		type Error = D::Error;
+
+		async fn read(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+			self.device.transaction(&mut [
+				Operation::Write(&[addr | READ]),
+				Operation::Read(buf),
+			]).await
+		}
+
+		async fn write(&mut self, addr: u8, value: u8) -> Result<(), Self::Error> {
+			self.device.write(&[addr & !READ, value]).await
+		}
+
+		async fn read_in_place(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+			let Some(first) = buf.first_mut() else {
+				return Ok(());
+			};
+			*first = addr | READ;
+			self.device.transfer_in_place(buf).await
+		}
+	}
+
+	impl<D: SpiDevice> SpiInterface for SpiTransport<D> {}
+}
+
+// Blocking SPI devices, e.g. embedded-hal-bus' `RefCellDevice` and `CriticalSectionDevice` sharing one bus.
+// The futures complete on their first poll.
+mod blocking_spi {
+	use embedded_hal::spi::{Operation, SpiDevice};
+
+	use super::{SpiInterface, Transport};
+
+	const READ: u8 = 0x80;
+
+	pub struct BlockingSpiTransport<D> {
+		device: D,
+	}
+
+	impl<D: SpiDevice> BlockingSpiTransport<D> {
+		pub fn new(device: D) -> Self {
+			Self { device }
+		}
+
+		pub fn release(self) -> D {
+			self.device
+		}
+	}
+
+	impl<D: SpiDevice> Transport for BlockingSpiTransport<D> {
+		type Error = D::Error;
+
+		async fn read(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+			self.device.transaction(&mut [
+				Operation::Write(&[addr | READ]),
+				Operation::Read(buf),
+			])
+		}
+
+		async fn write(&mut self, addr: u8, value: u8) -> Result<(), Self::Error> {
+			self.device.write(&[addr & !READ, value])
+		}
+
+		async fn read_in_place(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+			let Some(first) = buf.first_mut() else {
+				return Ok(());
+			};
+			*first = addr | READ;
+			self.device.transfer_in_place(buf)
+		}
+	}
+
+	impl<D: SpiDevice> SpiInterface for BlockingSpiTransport<D> {}
+}
+
+mod i3c {
+	use super::Transport;
+
+	// Controller side of an I3C bus in SDR mode. `address` is the target's dynamic address.
+	#[allow(async_fn_in_trait)]
+	pub trait I3cBus {
+		type Error;
+
+		async fn write_read(&mut self, address: u8, write: &[u8], read: &mut [u8]) -> Result<(), Self::Error>;
+		async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error>;
+		// Waits for an in-band interrupt from `address`, with its payload byte when INTF_CONFIG6.I3C_IBI_BYTE_EN is set.
+		async fn wait_for_ibi(&mut self, address: u8) -> Result<Option<u8>, Self::Error>;
+	}
+
+	pub struct I3cTransport<B> {
+		bus: B,
+		address: u8,
+	}
+
+	impl<B: I3cBus> I3cTransport<B> {
+		pub fn new(bus: B, address: u8) -> Self {
+			Self { bus, address }
+		}
+
+		pub fn address(&self) -> u8 {
+			self.address
+		}
+
+		pub fn wait_for_ibi(&mut self) -> impl Future<Output = Result<Option<u8>, B::Error>> {
+			self.bus.wait_for_ibi(self.address)
+		}
+
+		pub fn release(self) -> B {
+			self.bus
+		}
+	}
+
+	impl<B: I3cBus> Transport for I3cTransport<B> {
+		type Error = B::Error;
+
+		async fn read(&mut self, addr: u8, buf: &mut [u8]) -> Result<(), Self::Error> {
+			self.bus.write_read(self.address, &[addr], buf).await
+		}
+
+		async fn write(&mut self, addr: u8, value: u8) -> Result<(), Self::Error> {
+			self.bus.write(self.address, &[addr, value]).await
+		}
+	}
+}
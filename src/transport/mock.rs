@@ -0,0 +1,526 @@
+// Register file behind a fake I3C bus, for testing the driver on the host.
+use super::I3cBus;
+use crate::register::bank0::WhoAmI;
+
+const REG_BANK_SEL: u8 = 0x76;
+const WHO_AM_I: u8 = 0x75;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum MockError {
+	Nack(u8),  // nothing answers at this address
+	NoIbi,  // `wait_for_ibi` without a queued interrupt
+	InvalidBank(u8),  // REG_BANK_SEL written with a bank the register file doesn't have
+	Injected,  // a transfer starting at `MockI3cBus::fail_on`
+}
+
+pub struct MockI3cBus {
+	pub address: u8,
+	pub banks: [[u8; 128]; 5],  // bank 2 and 3 don't exist on the device and are never written through the driver
+	pub bank: u8,
+	pub ibi: Option<Option<u8>>,  // next in-band interrupt and its payload
+	pub fail_on: Option<(u8, u8)>,  // (bank, register) whose transfers fail, to test error paths
+}
+
+impl MockI3cBus {
+	pub fn new(address: u8) -> Self {
+		let mut banks = [[0u8; 128]; 5];
+		banks[0][WHO_AM_I as usize] = WhoAmI::ICM42688;
+		Self { address, banks, bank: 0, ibi: None, fail_on: None }
+	}
+
+	pub fn register(&self, bank: u8, addr: u8) -> u8 {
+		self.banks[bank as usize][addr as usize & 0x7F]
+	}
+
+	pub fn set_register(&mut self, bank: u8, addr: u8, value: u8) {
+		self.banks[bank as usize][addr as usize & 0x7F] = value;
+	}
+
+	pub fn raise_ibi(&mut self, payload: Option<u8>) {
+		self.ibi = Some(payload);
+	}
+
+	fn check(&self, address: u8) -> Result<(), MockError> {
+		if address == self.address { Ok(()) } else { Err(MockError::Nack(address)) }
+	}
+
+	fn check_register(&self, addr: u8) -> Result<(), MockError> {
+		if self.fail_on == Some((self.bank, addr & 0x7F)) { Err(MockError::Injected) } else { Ok(()) }
+	}
+}
+
+impl I3cBus for MockI3cBus {
+	type Error = MockError;
+
+	async fn write_read(&mut self, address: u8, write: &[u8], read: &mut [u8]) -> Result<(), Self::Error> {
+		self.check(address)?;
+		let start = write.first().copied().unwrap_or(0);
+		self.check_register(start)?;
+		for (i, byte) in read.iter_mut().enumerate() {
+			let addr = start.wrapping_add(i as u8) & 0x7F;
+			*byte = if addr == REG_BANK_SEL { self.bank } else { self.register(self.bank, addr) };
+		}
+		Ok(())
+	}
+
+	async fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+		self.check(address)?;
+		let Some((&start, values)) = write.split_first() else {
+			return Ok(());
+		};
+		self.check_register(start)?;
+		for (i, &value) in values.iter().enumerate() {
+			let addr = start.wrapping_add(i as u8) & 0x7F;
+			// REG_BANK_SEL is visible from every bank
+			if addr == REG_BANK_SEL {
+				let bank = value & 0b111;
+				if bank as usize >= self.banks.len() {
+					return Err(MockError::InvalidBank(bank));
+				}
+				self.bank = bank;
+				continue;
+			}
+			self.set_register(self.bank, addr, value);
+		}
+		Ok(())
+	}
+
+	async fn wait_for_ibi(&mut self, address: u8) -> Result<Option<u8>, Self::Error> {
+		self.check(address)?;
+		self.ibi.take().ok_or(MockError::NoIbi)
+	}
+}
+
+#[cfg(all(test, feature = "async"))]
+mod tests {
+	use embassy_futures::block_on;
+	use embedded_hal_async::delay::DelayNs;
+
+	use super::*;
+	use crate::transport::{I3cTransport, Transport};
+	use crate::Icm42688;
+
+	const ADDRESS: u8 = 0x68;
+	const SENSOR_CONFIG0: u8 = 0x03;
+
+	struct NoDelay;
+
+	impl DelayNs for NoDelay {
+		async fn delay_ns(&mut self, _ns: u32) {}
+	}
+
+	#[test]
+	fn init_checks_who_am_i() {
+		let mut imu = Icm42688::new_i3c(MockI3cBus::new(ADDRESS), ADDRESS);
+		assert!(block_on(imu.init(&mut NoDelay)).is_ok());
+
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, WHO_AM_I, 0x12);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		assert!(matches!(block_on(imu.init(&mut NoDelay)), Err(crate::Error::WhoAmI(0x12))));
+	}
+
+	#[test]
+	fn bank_switching_round_trip() {
+		let mut imu = Icm42688::new_i3c(MockI3cBus::new(ADDRESS), ADDRESS);
+		block_on(imu.init(&mut NoDelay)).unwrap();
+		block_on(imu.set_enabled_axes(crate::data::AxisMask::NONE, crate::data::AxisMask::ALL)).unwrap();
+
+		let bus = imu.release().release();
+		assert_eq!(bus.bank, 0);
+		assert_eq!(bus.register(1, SENSOR_CONFIG0), 0b111);
+		assert_eq!(bus.register(0, SENSOR_CONFIG0), 0);
+	}
+
+	#[test]
+	fn failed_transfers_return_to_bank_0() {
+		let mut imu = Icm42688::new_i3c(MockI3cBus::new(ADDRESS), ADDRESS);
+		block_on(imu.init(&mut NoDelay)).unwrap();
+		let mut bus = imu.release().release();
+		bus.fail_on = Some((1, SENSOR_CONFIG0));
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let result = block_on(imu.set_enabled_axes(crate::data::AxisMask::NONE, crate::data::AxisMask::ALL));
+		assert!(matches!(result, Err(crate::Error::Transport(MockError::Injected))));
+		assert_eq!(imu.enabled_axes().0, crate::data::AxisMask::ALL);
+
+		let bus = imu.release().release();
+		assert_eq!(bus.bank, 0);
+		assert_eq!(bus.register(1, SENSOR_CONFIG0), 0);
+	}
+
+	#[test]
+	fn read_tmstval_reads_bank_1() {
+		const TMSTVAL0: u8 = 0x62;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		// the top nibble of TMSTVAL2 is reserved
+		for (i, byte) in [0x56, 0x34, 0xF2].into_iter().enumerate() {
+			bus.set_register(1, TMSTVAL0 + i as u8, byte);
+		}
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		assert_eq!(block_on(imu.read_tmstval()).unwrap(), 0x2_3456);
+		assert_eq!(block_on(imu.read_timestamp_us()).unwrap(), 0x2_3456);
+		assert_eq!(imu.release().release().bank, 0);
+	}
+
+	#[test]
+	fn clkin_frequency_is_checked_and_lp_clock_kept() {
+		const INTF_CONFIG1: u8 = 0x4D;
+		const INTF_CONFIG5: u8 = 0x7B;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		// reserved bits and the RC oscillator for low power accel
+		bus.set_register(0, INTF_CONFIG1, 0b1001_1001);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		assert!(matches!(block_on(imu.enable_clkin(0)), Err(crate::Error::ClkinFrequency(0))));
+		assert!(matches!(block_on(imu.enable_clkin(60_000)), Err(crate::Error::ClkinFrequency(60_000))));
+		assert_eq!(imu.clock_input(), crate::clock::ClockInput::Internal);
+
+		block_on(imu.enable_clkin(32_768)).unwrap();
+		assert_eq!(imu.clock_input(), crate::clock::ClockInput::Clkin(32_768));
+		block_on(imu.disable_clkin()).unwrap();
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, INTF_CONFIG1), 0b1001_1001);
+		assert_eq!(bus.register(1, INTF_CONFIG5) >> 1 & 0b11, 0);
+	}
+
+	#[test]
+	fn fsync_event_reads_the_tagged_flag_and_delta() {
+		use crate::register::bank0::{FsyncPolarity, FsyncUiFlagClearSel, FsyncUiSel};
+		use crate::timestamp::FsyncSettings;
+
+		const ACCEL_DATA_Z0: u8 = 0x24;
+		const TMST_FSYNCH: u8 = 0x2B;
+		const INTF_CONFIG5: u8 = 0x7B;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, ACCEL_DATA_Z0, 0x01);
+		bus.set_register(0, TMST_FSYNCH, 0x01);
+		bus.set_register(0, TMST_FSYNCH + 1, 0x2C);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		// the reset selection tags TEMP_DATA0, whose flag is clear
+		assert_eq!(block_on(imu.read_fsync_event()).unwrap(), None);
+
+		let settings = FsyncSettings {
+			ui_sel: FsyncUiSel::AccelZOut,
+			flag_clear: FsyncUiFlagClearSel::OnUpdate,
+			polarity: FsyncPolarity::RisingEdge,
+		};
+		block_on(imu.enable_fsync(settings)).unwrap();
+		assert_eq!(block_on(imu.read_fsync_event()).unwrap(), Some(300));
+		block_on(imu.disable_fsync()).unwrap();
+		assert_eq!(block_on(imu.read_fsync_event()).unwrap(), None);
+		assert_eq!(imu.release().release().register(1, INTF_CONFIG5), 0);
+	}
+
+	#[test]
+	fn wake_on_motion_follows_the_accel() {
+		use crate::motion::WomSettings;
+		use crate::register::bank0::{AccelFs, AccelMode, GyroMode, Odr, WomMode};
+
+		const INTF_CONFIG1: u8 = 0x4D;
+		const ACCEL_WOM_X_THR: u8 = 0x4A;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, INTF_CONFIG1, 0b1000);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let settings = WomSettings { odr: Odr::Hz1k, ..WomSettings::new(98, WomMode::ComparePrevious) };
+		assert!(matches!(block_on(imu.configure_wake_on_motion(settings, &mut NoDelay)), Err(crate::Error::LowPowerOdr(Odr::Hz1k))));
+		assert!(imu.wake_on_motion().is_none());
+
+		block_on(imu.enable_wake_on_motion(98, WomMode::ComparePrevious, &mut NoDelay)).unwrap();
+		block_on(imu.configure_accel(AccelFs::G2, Odr::Hz100)).unwrap();
+		assert_eq!(imu.wake_on_motion().unwrap().settings().odr, Odr::Hz100);
+		block_on(imu.set_power(GyroMode::Off, AccelMode::Off, &mut NoDelay)).unwrap();
+		assert_eq!(imu.wom_settings(), None);
+
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, INTF_CONFIG1), 0);
+		assert_eq!(bus.register(4, ACCEL_WOM_X_THR), 25);
+		assert_eq!(bus.bank, 0);
+	}
+
+	#[test]
+	fn significant_motion_on_top_of_wake_on_motion() {
+		use crate::interrupt::InterruptPin;
+		use crate::motion::SmdWindow;
+		use crate::register::bank0::WomMode;
+
+		const INT_STATUS2: u8 = 0x37;
+		const SMD_CONFIG: u8 = 0x57;
+		const INT_SOURCE1: u8 = 0x66;
+		const INT_SOURCE4: u8 = 0x69;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, INT_STATUS2, 0b1001);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let wom = block_on(imu.enable_wake_on_motion(98, WomMode::ComparePrevious, &mut NoDelay)).unwrap();
+		let mut smd = block_on(wom.enable_significant_motion(SmdWindow::Long, InterruptPin::Int2)).unwrap();
+		let status = block_on(smd.read_status()).unwrap();
+		assert!(status.smd && status.wom_x && !status.wom_y);
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, SMD_CONFIG), 0b0111);
+		assert_eq!(bus.register(0, INT_SOURCE1), 0b0111);
+		assert_eq!(bus.register(0, INT_SOURCE4), 0b1000);
+
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let wom = block_on(imu.enable_wake_on_motion(98, WomMode::ComparePrevious, &mut NoDelay)).unwrap();
+		let smd = block_on(wom.enable_significant_motion(SmdWindow::Short, InterruptPin::Int1)).unwrap();
+		block_on(smd.disable()).unwrap();
+		block_on(imu.disable_wake_on_motion()).unwrap();
+		assert!(imu.wake_on_motion().is_none());
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, SMD_CONFIG), 0b0100);
+		assert_eq!((bus.register(0, INT_SOURCE1), bus.register(0, INT_SOURCE4)), (0, 0));
+	}
+
+	#[test]
+	fn pedometer_setup_and_readout() {
+		use crate::apex::{PedometerData, PedometerSettings};
+		use crate::interrupt::InterruptPin;
+		use crate::register::bank0::{ActivityClass, DmpOdr};
+
+		const APEX_DATA0: u8 = 0x31;
+		const APEX_CONFIG0: u8 = 0x56;
+		const PWR_MGMT0: u8 = 0x4E;
+		const APEX_CONFIG1: u8 = 0x40;
+		const INT_SOURCE6: u8 = 0x4D;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		for (i, byte) in [0x34, 0x12, 40, 0b110].into_iter().enumerate() {
+			bus.set_register(0, APEX_DATA0 + i as u8, byte);
+		}
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let settings = PedometerSettings { pin: Some(InterruptPin::Int1), ..PedometerSettings::default() };
+		block_on(imu.enable_pedometer(settings, &mut NoDelay)).unwrap();
+		let data = block_on(imu.read_pedometer()).unwrap();
+		assert_eq!(data, PedometerData { step_count: 0x1234, cadence_raw: 40, activity: ActivityClass::Run, dmp_idle: true });
+		assert_eq!(data.cadence_hz(DmpOdr::Hz50), 5.0);
+
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, APEX_CONFIG0), 0b1010_0010);
+		assert_eq!(bus.register(0, PWR_MGMT0) & 0b11, 0b10);
+		assert_eq!(&bus.banks[4][APEX_CONFIG1 as usize..APEX_CONFIG1 as usize + 3], &[0xA2, 0x85, 0x51]);
+		assert_eq!(bus.register(4, INT_SOURCE6), 0b10_0000);
+		assert_eq!(bus.bank, 0);
+	}
+
+	#[test]
+	fn tilt_detection_keeps_reserved_bits() {
+		use crate::apex::{TiltEvent, TiltSettings};
+		use crate::interrupt::InterruptPin;
+		use crate::register::bank4::TiltWaitTime;
+
+		const INT_STATUS3: u8 = 0x38;
+		const APEX_CONFIG4: u8 = 0x43;
+		const INT_SOURCE7: u8 = 0x4E;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		// reset value of the reserved bits
+		bus.set_register(4, APEX_CONFIG4, 0b100);
+		bus.set_register(0, INT_STATUS3, 0b1000);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		block_on(imu.enable_tilt_detection(TiltSettings { wait_time: TiltWaitTime::S2, pin: Some(InterruptPin::Int2) }, &mut NoDelay)).unwrap();
+		assert_eq!(block_on(imu.read_apex_status()).unwrap().tilt_event(), Some(TiltEvent));
+
+		let bus = imu.release().release();
+		// S2, the raise-to-sleep reset timeout 6.4 s, reserved 0b100
+		assert_eq!(bus.register(4, APEX_CONFIG4), 0b01_100_100);
+		assert_eq!(bus.register(4, INT_SOURCE7), 0b1000);
+	}
+
+	#[test]
+	fn in_place_fifo_read_takes_whole_packets() {
+		use crate::fifo::FifoSettings;
+
+		const FIFO_COUNTH: u8 = 0x2E;
+		const FIFO_DATA: u8 = 0x30;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		// 20 bytes counted, one 16 byte packet fits
+		bus.set_register(0, FIFO_COUNTH + 1, 20);
+		let packet = [0x60, 0x12, 0x34, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+		for (i, &value) in packet.iter().enumerate() {
+			bus.set_register(0, FIFO_DATA + i as u8, value);
+		}
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		block_on(imu.configure_fifo(FifoSettings::default())).unwrap();
+		let mut buf = [0u8; 1 + 31];
+		let mut packets = block_on(imu.read_fifo_in_place(&mut buf)).unwrap();
+		assert_eq!(packets.next().unwrap().accel, Some([0x1234, 0, 0]));
+		assert!(packets.next().is_none());
+
+		// neither an empty FIFO nor a buffer without room for data reads FIFO_DATA
+		let mut bus = imu.release().release();
+		bus.set_register(0, FIFO_COUNTH + 1, 0);
+		bus.fail_on = Some((0, FIFO_DATA));
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		assert!(block_on(imu.read_fifo_in_place(&mut buf)).unwrap().next().is_none());
+		assert!(block_on(imu.read_fifo_in_place(&mut [0u8; 1])).unwrap().next().is_none());
+	}
+
+	#[test]
+	fn read_all_is_one_burst_from_temp_data() {
+		const TEMP_DATA1: u8 = 0x1D;
+		const ACCEL_DATA_X1: u8 = 0x1F;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		for (i, value) in (1..=14).enumerate() {
+			bus.set_register(0, TEMP_DATA1 + i as u8, value);
+		}
+		// a separate accel read would start here
+		bus.fail_on = Some((0, ACCEL_DATA_X1));
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let sample = block_on(imu.read_all()).unwrap();
+		assert_eq!(sample.temperature.raw, 0x0102);
+		assert_eq!(sample.accel.raw, [0x0304, 0x0506, 0x0708]);
+		assert_eq!(sample.gyro.raw, [0x090A, 0x0B0C, 0x0D0E]);
+	}
+
+	#[test]
+	fn disabling_i2c_i3c_keeps_the_fifo_settings() {
+		const INTF_CONFIG0: u8 = 0x4C;
+		const INTF_CONFIG6: u8 = 0x7C;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, INTF_CONFIG0, 0b1011_0000);
+		bus.set_register(1, INTF_CONFIG6, 0b0101_1111);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		block_on(imu.disable_i2c_i3c()).unwrap();
+
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, INTF_CONFIG0), 0b1011_0011);
+		assert_eq!(bus.register(1, INTF_CONFIG6), 0b0100_0000);
+		assert_eq!(bus.bank, 0);
+	}
+
+	#[test]
+	fn interface_configuration_keeps_reserved_bits() {
+		use crate::interface::InterfaceConfig;
+		use crate::register::bank0::{Endian, SlewRate};
+		use crate::register::bank1::SpiWire;
+
+		const DRIVE_CONFIG: u8 = 0x13;
+		const ACCEL_DATA_X1: u8 = 0x1F;
+		const INTF_CONFIG0: u8 = 0x4C;
+		const INTF_CONFIG4: u8 = 0x7A;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, DRIVE_CONFIG, 0b1100_0000);
+		bus.set_register(0, INTF_CONFIG0, 0b0011_1111);
+		bus.set_register(1, INTF_CONFIG4, 0b1100_0011);
+		bus.set_register(0, ACCEL_DATA_X1, 0x34);
+		bus.set_register(0, ACCEL_DATA_X1 + 1, 0x12);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let config = InterfaceConfig {
+			sensor_data_endian: Endian::Little,
+			fifo_count_endian: Endian::Little,
+			fifo_count_records: false,
+			fifo_hold_last_data: true,
+		};
+		block_on(imu.configure_interface(config)).unwrap();
+		assert_eq!(block_on(imu.read_accel()).unwrap().raw[0], 0x1234);
+		block_on(imu.set_spi_wire(SpiWire::ThreeWire)).unwrap();
+		block_on(imu.set_slew_rate(SlewRate::Ns2To6, SlewRate::Under2Ns)).unwrap();
+
+		let bus = imu.release().release();
+		assert_eq!(bus.register(0, INTF_CONFIG0), 0b1000_1111);
+		assert_eq!(bus.register(1, INTF_CONFIG4), 0b1100_0001);
+		assert_eq!(bus.register(0, DRIVE_CONFIG), 0b1110_0101);
+		assert_eq!(bus.bank, 0);
+	}
+
+	#[test]
+	fn reg_bank_sel_reads_back_from_every_bank() {
+		let mut transport = I3cTransport::new(MockI3cBus::new(ADDRESS), ADDRESS);
+		for bank in 0..5 {
+			block_on(transport.write(REG_BANK_SEL, bank)).unwrap();
+			let mut value = [0];
+			block_on(transport.read(REG_BANK_SEL, &mut value)).unwrap();
+			assert_eq!(value[0], bank);
+		}
+	}
+
+	#[test]
+	fn missing_banks_are_rejected() {
+		let mut transport = I3cTransport::new(MockI3cBus::new(ADDRESS), ADDRESS);
+		assert_eq!(block_on(transport.write(REG_BANK_SEL, 5)), Err(MockError::InvalidBank(5)));
+		let mut value = [0];
+		block_on(transport.read(WHO_AM_I, &mut value)).unwrap();
+		assert_eq!(value[0], WhoAmI::ICM42688);
+	}
+
+	#[test]
+	fn read_fifo_in_place_without_room_or_data() {
+		let mut imu = Icm42688::new_i3c(MockI3cBus::new(ADDRESS), ADDRESS);
+		block_on(imu.init(&mut NoDelay)).unwrap();
+		assert_eq!(block_on(imu.read_fifo_in_place(&mut [])).unwrap().count(), 0);
+		assert_eq!(block_on(imu.read_fifo_in_place(&mut [0; 17])).unwrap().count(), 0);
+	}
+
+	#[test]
+	fn raise_to_wake_needs_a_dmp_mounting_and_keeps_reserved_bits() {
+		use crate::apex::R2wSettings;
+		use crate::mounting::{AxisRotation, SignedAxis};
+
+		const APEX_CONFIG0: u8 = 0x56;
+		const APEX_CONFIG6: u8 = 0x45;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(4, APEX_CONFIG6, 0b1010_1000);
+		let unsupported = AxisRotation::new(SignedAxis::PosY, SignedAxis::PosZ).unwrap().into();
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS).with_mounting(unsupported);
+		assert!(matches!(block_on(imu.enable_raise_to_wake(R2wSettings::default(), &mut NoDelay)), Err(crate::Error::Mounting)));
+		assert_eq!(imu.apex_settings().r2w, None);
+
+		let mut imu = Icm42688::new_i3c(imu.release().release(), ADDRESS);
+		let settings = R2wSettings { sleep_gesture_delay: 2, ..R2wSettings::default() };
+		block_on(imu.enable_raise_to_wake(settings, &mut NoDelay)).unwrap();
+		let bus = imu.release().release();
+		assert_eq!(bus.register(4, APEX_CONFIG6), 0b1010_1010);
+		assert_eq!(bus.register(0, APEX_CONFIG0) >> 3 & 0b1, 1);
+	}
+
+	#[test]
+	fn tap_detection_setup_and_event() {
+		use crate::apex::{TapCount, TapSettings};
+		use crate::register::bank0::TapAxis;
+		use crate::register::bank4::SensitivityMode;
+
+		const APEX_DATA4: u8 = 0x35;
+		const INT_STATUS3: u8 = 0x38;
+		const PWR_MGMT0: u8 = 0x4E;
+		const APEX_CONFIG7: u8 = 0x46;
+		const APEX_CONFIG9: u8 = 0x48;
+		let mut bus = MockI3cBus::new(ADDRESS);
+		bus.set_register(0, APEX_DATA4, 0b1_0010);
+		bus.set_register(0, APEX_DATA4 + 1, 7);
+		bus.set_register(4, APEX_CONFIG9, 0b1010_1010);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let status = block_on(imu.read_apex_status()).unwrap();
+		assert_eq!(block_on(imu.tap_event(&status)).unwrap(), None);
+
+		block_on(imu.enable_tap_detection(TapSettings::default(), &mut NoDelay)).unwrap();
+		block_on(imu.set_dmp_sensitivity(SensitivityMode::HighPerformance, &mut NoDelay)).unwrap();
+		let mut bus = imu.release().release();
+		assert_eq!(bus.register(0, PWR_MGMT0) & 0b11, 0b11);
+		assert_eq!(&bus.banks[4][APEX_CONFIG7 as usize..APEX_CONFIG7 as usize + 3], &[17 << 2 | 1, 2 << 5 | 3 << 3 | 3, 0b1010_1011]);
+
+		bus.set_register(0, INT_STATUS3, 0b1);
+		let mut imu = Icm42688::new_i3c(bus, ADDRESS);
+		let status = block_on(imu.read_apex_status()).unwrap();
+		let tap = block_on(imu.tap_event(&status)).unwrap().unwrap();
+		assert_eq!((tap.count, tap.axis, tap.double_tap_timing), (TapCount::Double, TapAxis::Y, 7));
+	}
+
+	#[test]
+	fn set_mounting_keeps_the_old_mounting_when_the_dmp_cant_follow() {
+		use crate::apex::R2wSettings;
+		use crate::mounting::{AxisRotation, Mounting, SignedAxis};
+		use crate::register::bank4::MountingOrientation;
+
+		const APEX_CONFIG5: u8 = 0x44;
+		let mut imu = Icm42688::new_i3c(MockI3cBus::new(ADDRESS), ADDRESS);
+		block_on(imu.init(&mut NoDelay)).unwrap();
+		block_on(imu.enable_raise_to_wake(R2wSettings::default(), &mut NoDelay)).unwrap();
+
+		let unsupported: Mounting = AxisRotation::new(SignedAxis::PosY, SignedAxis::PosZ).unwrap().into();
+		assert!(matches!(block_on(imu.set_mounting(unsupported, &mut NoDelay)), Err(crate::Error::Mounting)));
+		assert_eq!(imu.mounting(), Mounting::IDENTITY);
+
+		let rot_z90: Mounting = AxisRotation::new(SignedAxis::PosY, SignedAxis::NegX).unwrap().into();
+		block_on(imu.set_mounting(rot_z90, &mut NoDelay)).unwrap();
+		assert_eq!(imu.mounting(), rot_z90);
+		let bus = imu.release().release();
+		assert_eq!(bus.register(4, APEX_CONFIG5), MountingOrientation::RotZ90 as u8);
+	}
+}